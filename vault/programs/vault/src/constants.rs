@@ -2,3 +2,30 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+#[constant]
+pub const PAUSE_DEPOSIT: u8 = 1 << 0;
+
+#[constant]
+pub const PAUSE_WITHDRAW: u8 = 1 << 1;
+
+#[constant]
+pub const PAUSE_MINT: u8 = 1 << 2;
+
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSIT | PAUSE_WITHDRAW | PAUSE_MINT;
+
+#[constant]
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+/// Upper bound on deposit and withdraw fees (10%).
+#[constant]
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+#[constant]
+pub const MAX_LOCKUP_TIERS: usize = 4;
+
+#[constant]
+pub const MAX_MINTERS: usize = 8;
+
+/// Fixed-point scale of `Config::acc_reward_per_share`.
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
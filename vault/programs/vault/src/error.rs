@@ -4,4 +4,74 @@ use anchor_lang::prelude::*;
 pub enum VaultError {
     #[msg("You don't have this much of amount deposited in the vault!")]
     AmountExceededUrDeposit,
+    #[msg("Arithmetic overflow in vault accounting")]
+    MathOverflow,
+    #[msg("Deposit is too small to mint any shares")]
+    ZeroShares,
+    #[msg("Withdraw the full balance before closing the position")]
+    PositionNotEmpty,
+    #[msg("This account is not the vault admin")]
+    NotAdmin,
+    #[msg("This account is not the pending vault admin")]
+    NotPendingAdmin,
+    #[msg("This account is neither the vault admin nor the pauser")]
+    NotPauser,
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
+    #[msg("This vault operation is paused")]
+    Paused,
+    #[msg("Deposit would exceed the vault's total deposit cap")]
+    DepositCapExceeded,
+    #[msg("Deposit would exceed the per-user deposit cap")]
+    UserDepositCapExceeded,
+    #[msg("This account is neither the vault admin nor the fee manager")]
+    NotFeeManager,
+    #[msg("Fee is above the allowed maximum")]
+    FeeTooHigh,
+    #[msg("There are no accrued fees to collect")]
+    NoFeesToCollect,
+    #[msg("Withdrawals go through request_withdraw while a cooldown is configured")]
+    CooldownActive,
+    #[msg("The withdrawal cooldown has not elapsed yet")]
+    CooldownNotElapsed,
+    #[msg("Cooldown must not be negative")]
+    InvalidCooldown,
+    #[msg("Unknown or disabled lockup tier")]
+    InvalidLockupTier,
+    #[msg("This position is still locked")]
+    PositionLocked,
+    #[msg("Claim outstanding rewards before closing the position")]
+    UnclaimedRewards,
+    #[msg("The reward mint cannot be changed once set")]
+    RewardMintMismatch,
+    #[msg("There are no rewards to claim")]
+    NoRewardsToClaim,
+    #[msg("The mint's transfer hook program or ExtraAccountMetaList is missing")]
+    MissingHookAccounts,
+    #[msg("The transfer hook program does not match the one recorded at init")]
+    HookProgramMismatch,
+    #[msg("The transfer hook program is not executable")]
+    HookProgramNotExecutable,
+    #[msg("The ExtraAccountMetaList is not the hook program's PDA for this mint")]
+    InvalidExtraAccountMetaList,
+    #[msg("This account is not on the minter allow-list")]
+    NotMinter,
+    #[msg("The minter allow-list is full")]
+    MinterListFull,
+    #[msg("This account is already a minter")]
+    AlreadyMinter,
+    #[msg("Minting would exceed the max supply")]
+    MaxSupplyExceeded,
+    #[msg("Batch mint needs exactly one amount per recipient account")]
+    BatchLengthMismatch,
+    #[msg("Recipient is not a token account of the vault mint")]
+    InvalidRecipient,
+    #[msg("The reward mint cannot be the vault's own mint")]
+    RewardMintIsVaultMint,
+    #[msg("The reward mint cannot be the vault's share mint")]
+    RewardMintIsShareMint,
+    #[msg("Claim or cancel the pending withdrawal before closing the position")]
+    WithdrawalPending,
+    #[msg("This position has no lock multiplier to expire")]
+    NoLockToExpire,
 }
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct VaultInitialized {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub share_mint: Pubkey,
+    pub transfer_hook_program: Pubkey,
+    pub decimals: u8,
+}
+
+#[event]
+pub struct Deposited {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    /// Amount that reached the vault, after any Token-2022 transfer fee.
+    pub received: u64,
+    pub fee: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct Withdrawn {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    /// Amount redeemed, including the withdraw fee kept in the vault.
+    pub amount: u64,
+    pub fee: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct Minted {
+    pub config: Pubkey,
+    pub minter: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct Staked {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+}
+
+#[event]
+pub struct LockExpired {
+    pub config: Pubkey,
+    pub user: Pubkey,
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::VaultError, Config, LockupTier, Role, BPS_DENOMINATOR};
+
+#[derive(Accounts)]
+pub struct AdminOperations<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", config.mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ VaultError::NotAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl AdminOperations<'_> {
+    /// First half of the handover; the new admin only takes over once it accepts.
+    pub fn propose_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        self.config.pending_admin = Some(new_admin);
+        Ok(())
+    }
+
+    pub fn set_role(&mut self, role: Role, account: Pubkey) -> Result<()> {
+        match role {
+            Role::Pauser => self.config.pauser = account,
+            Role::FeeManager => self.config.fee_manager = account,
+        }
+        Ok(())
+    }
+
+    pub fn add_minter(&mut self, minter: Pubkey) -> Result<()> {
+        require!(!self.config.is_minter(&minter), VaultError::AlreadyMinter);
+
+        let slot = self
+            .config
+            .minters
+            .iter_mut()
+            .find(|slot| **slot == Pubkey::default())
+            .ok_or(VaultError::MinterListFull)?;
+        *slot = minter;
+        Ok(())
+    }
+
+    pub fn remove_minter(&mut self, minter: Pubkey) -> Result<()> {
+        require!(minter != Pubkey::default(), VaultError::NotMinter);
+
+        let slot = self
+            .config
+            .minters
+            .iter_mut()
+            .find(|slot| **slot == minter)
+            .ok_or(VaultError::NotMinter)?;
+        *slot = Pubkey::default();
+        Ok(())
+    }
+
+    /// Only bounds future mints; a cap below the current supply just stops minting.
+    pub fn set_max_supply(&mut self, max_supply: u64) -> Result<()> {
+        self.config.max_supply = max_supply;
+        Ok(())
+    }
+
+    pub fn set_deposit_limits(&mut self, max_total_deposits: u64, max_per_user: u64) -> Result<()> {
+        self.config.max_total_deposits = max_total_deposits;
+        self.config.max_per_user = max_per_user;
+        Ok(())
+    }
+
+    pub fn set_withdraw_cooldown(&mut self, withdraw_cooldown: i64) -> Result<()> {
+        require!(withdraw_cooldown >= 0, VaultError::InvalidCooldown);
+
+        self.config.withdraw_cooldown = withdraw_cooldown;
+        Ok(())
+    }
+
+    /// Configures one lockup tier; a zero `duration` disables it for new deposits.
+    pub fn set_lockup_tier(&mut self, index: u8, duration: i64, multiplier_bps: u16) -> Result<()> {
+        require!(
+            duration >= 0 && multiplier_bps >= BPS_DENOMINATOR,
+            VaultError::InvalidLockupTier
+        );
+
+        let tier = self
+            .config
+            .lockup_tiers
+            .get_mut(index as usize)
+            .ok_or(VaultError::InvalidLockupTier)?;
+        *tier = LockupTier {
+            duration,
+            multiplier_bps,
+        };
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", config.mint.as_ref()],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(new_admin.key()) @ VaultError::NotPendingAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl AcceptAdmin<'_> {
+    pub fn accept_admin(&mut self) -> Result<()> {
+        self.config.admin = self.new_admin.key();
+        self.config.pending_admin = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::VaultError, Amount, Config};
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"amount", config.key().as_ref(), user.key().as_ref()],
+        bump=amount_pda.bump,
+    )]
+    pub amount_pda: Account<'info, Amount>,
+
+    #[account(seeds = [b"config", config.mint.as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: only checked for existence; claim and cancel need the position open
+    #[account(
+        seeds = [b"withdrawal", config.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub pending_withdrawal: UncheckedAccount<'info>,
+}
+
+impl ClosePosition<'_> {
+    pub fn close_position(&mut self) -> Result<()> {
+        require!(self.amount_pda.shares == 0, VaultError::PositionNotEmpty);
+        require!(
+            self.amount_pda.pending_rewards == 0,
+            VaultError::UnclaimedRewards
+        );
+        require!(
+            self.pending_withdrawal.data_is_empty(),
+            VaultError::WithdrawalPending
+        );
+
+        self.amount_pda.close(self.user.to_account_info())?;
+        Ok(())
+    }
+}
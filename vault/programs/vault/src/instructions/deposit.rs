@@ -1,12 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
 };
 
 use spl_token_2022::onchain::invoke_transfer_checked;
 
-use crate::{Amount, Config};
+use crate::{error::VaultError, utils::hook_accounts, Amount, Config, Deposited, PAUSE_DEPOSIT};
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
@@ -16,18 +16,17 @@ pub struct Deposit<'info> {
     #[account(
         init_if_needed,
         payer=user,
-        seeds=[b"amount", user.key().as_ref()],
+        seeds=[b"amount", config.key().as_ref(), user.key().as_ref()],
         bump,
         space = 8 + Amount::INIT_SPACE
     )]
     pub amount_pda: Account<'info, Amount>,
 
-    #[account( seeds = [b"config"], bump=config.bump)]
+    #[account(mut, seeds = [b"config", mint.key().as_ref()], bump = config.bump)]
     pub config: Account<'info, Config>,
 
     #[account(
-        mut,
-        mint::decimals = 6,
+        mint::decimals = config.decimals,
         mint::token_program = token_program,
     )]
     pub mint: InterfaceAccount<'info, Mint>,
@@ -48,28 +47,42 @@ pub struct Deposit<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: ExtraAccountMetalist Account
-    pub extra_account_meta_list: UncheckedAccount<'info>,
-    /// CHECK: ExtraAccountMetalist Account
-    pub whitelist: UncheckedAccount<'info>,
-    /// CHECK: this will be the program created for the whitelist tf hook
-    pub transfer_hook_program: UncheckedAccount<'info>,
+    #[account(mut, address = config.share_mint)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Holds the shares of every position; they leave it through `unstake` or a withdrawal.
+    #[account(
+        init_if_needed,
+        payer=user,
+        associated_token::mint=share_mint,
+        associated_token::authority=config,
+        associated_token::token_program=token_program
+    )]
+    pub share_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-impl Deposit<'_> {
-    pub fn deposit(&mut self, amount: u64, bumps: &DepositBumps) -> Result<()> {
-        self.amount_pda.set_inner(Amount {
-            amount: self
-                .amount_pda
-                .amount
-                .checked_add(amount)
-                .expect("Failed to add amount, account Overflow!"),
-            bump: bumps.amount_pda,
-        });
+impl<'info> Deposit<'info> {
+    pub fn deposit(
+        &mut self,
+        amount: u64,
+        bumps: &DepositBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_DEPOSIT), VaultError::Paused);
+
+        let now = Clock::get()?.unix_timestamp;
+        self.sync_rewards(now)?;
+
+        // price the deposit before the vault balance moves
+        let balance_before = self.vault.amount;
+        let total_assets = self
+            .config
+            .total_assets(balance_before)
+            .ok_or(VaultError::MathOverflow)?;
 
         invoke_transfer_checked(
             &self.token_program.key(),
@@ -77,15 +90,134 @@ impl Deposit<'_> {
             self.mint.to_account_info(),
             self.vault.to_account_info(),
             self.user.to_account_info(),
-            &[
-                self.extra_account_meta_list.to_account_info(),
-                self.whitelist.to_account_info(),
-                self.transfer_hook_program.to_account_info(),
-            ],
+            hook_accounts(
+                &self.mint.to_account_info(),
+                self.config.transfer_hook_program,
+                remaining_accounts,
+            )?,
             amount,
             self.mint.decimals,
             &[],
         )?;
+
+        // a Token-2022 transfer fee is withheld on arrival, so only credit what landed
+        self.vault.reload()?;
+        let received = self
+            .vault
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(VaultError::MathOverflow)?;
+
+        let fee = Config::fee(received, self.config.deposit_fee_bps);
+        let credited = received - fee;
+        let shares = Config::convert_to_shares(credited, total_assets, self.share_mint.supply)
+            .ok_or(VaultError::MathOverflow)?;
+        require!(shares > 0, VaultError::ZeroShares);
+
+        let total_deposits = total_assets
+            .checked_add(credited)
+            .ok_or(VaultError::MathOverflow)?;
+        require!(
+            total_deposits <= self.config.max_total_deposits,
+            VaultError::DepositCapExceeded
+        );
+
+        let user_deposits = self
+            .amount_pda
+            .deposited
+            .checked_add(credited)
+            .ok_or(VaultError::MathOverflow)?;
+        require!(
+            user_deposits <= self.config.max_per_user,
+            VaultError::UserDepositCapExceeded
+        );
+
+        self.amount_pda.deposited = user_deposits;
+        self.amount_pda.shares = self
+            .amount_pda
+            .shares
+            .checked_add(shares)
+            .ok_or(VaultError::MathOverflow)?;
+        self.amount_pda.bump = bumps.amount_pda;
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
+
+        self.config.accrued_fees = self
+            .config
+            .accrued_fees
+            .checked_add(fee)
+            .ok_or(VaultError::MathOverflow)?;
+
+        self.mint_shares(shares)?;
+
+        emit!(Deposited {
+            config: self.config.key(),
+            user: self.user.key(),
+            received,
+            fee,
+            shares,
+        });
+        Ok(())
+    }
+
+    /// Deposits and locks the whole position for the chosen tier. Locks only ever
+    /// extend: if the position is already locked past the tier's unlock time,
+    /// its current lock and multiplier are kept, otherwise both come from the tier.
+    pub fn deposit_locked(
+        &mut self,
+        amount: u64,
+        tier: u8,
+        bumps: &DepositBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let tier = self
+            .config
+            .lockup_tiers
+            .get(tier as usize)
+            .copied()
+            .filter(|tier| tier.duration > 0)
+            .ok_or(VaultError::InvalidLockupTier)?;
+
+        self.deposit(amount, bumps, remaining_accounts)?;
+
+        let locked_until = Clock::get()?
+            .unix_timestamp
+            .checked_add(tier.duration)
+            .ok_or(VaultError::MathOverflow)?;
+        if locked_until > self.amount_pda.locked_until {
+            self.amount_pda.locked_until = locked_until;
+            self.amount_pda.multiplier_bps = tier.multiplier_bps;
+        }
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
         Ok(())
     }
+
+    fn sync_rewards(&mut self, now: i64) -> Result<()> {
+        self.config
+            .sync_position(&mut self.amount_pda, now)
+            .ok_or(VaultError::MathOverflow)?;
+        Ok(())
+    }
+
+    fn mint_shares(&self, shares: u64) -> Result<()> {
+        let mint_key = self.mint.key();
+        let seeds: &[&[u8]] = &[b"config", mint_key.as_ref(), &[self.config.bump]];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        let cpi_accounts = MintTo {
+            mint: self.share_mint.to_account_info(),
+            to: self.share_escrow.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+
+        mint_to(cpi_ctx, shares)
+    }
 }
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{
+        harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint, Mint, TokenAccount,
+        TokenInterface,
+    },
+};
+
+use spl_token_2022::onchain::invoke_transfer_checked;
+
+use crate::{error::VaultError, utils::hook_accounts, Config, MAX_FEE_BPS};
+
+#[derive(Accounts)]
+pub struct FeeOperations<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", config.mint.as_ref()],
+        bump = config.bump,
+        constraint = authority.key() == config.admin || authority.key() == config.fee_manager @ VaultError::NotFeeManager,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl FeeOperations<'_> {
+    pub fn set_fees(
+        &mut self,
+        deposit_fee_bps: u16,
+        withdraw_fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        require!(
+            deposit_fee_bps <= MAX_FEE_BPS && withdraw_fee_bps <= MAX_FEE_BPS,
+            VaultError::FeeTooHigh
+        );
+
+        self.config.deposit_fee_bps = deposit_fee_bps;
+        self.config.withdraw_fee_bps = withdraw_fee_bps;
+        self.config.treasury = treasury;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump = config.bump,
+        constraint = authority.key() == config.admin || authority.key() == config.fee_manager @ VaultError::NotFeeManager,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mint::decimals = config.decimals,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, associated_token::mint = mint, associated_token::authority = config, associated_token::token_program=token_program)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = config.treasury,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CollectFees<'info> {
+    pub fn collect_fees(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let amount = self.config.accrued_fees;
+        require!(amount > 0, VaultError::NoFeesToCollect);
+
+        let mint_key = self.mint.key();
+        let seeds: &[&[u8]] = &[b"config", mint_key.as_ref(), &[self.config.bump]];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        invoke_transfer_checked(
+            &self.token_program.key(),
+            self.vault.to_account_info(),
+            self.mint.to_account_info(),
+            self.treasury.to_account_info(),
+            self.config.to_account_info(),
+            hook_accounts(
+                &self.mint.to_account_info(),
+                self.config.transfer_hook_program,
+                remaining_accounts,
+            )?,
+            amount,
+            self.mint.decimals,
+            signer_seeds,
+        )?;
+
+        self.config.accrued_fees = 0;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct HarvestWithheldFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = admin @ VaultError::NotAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, associated_token::mint = mint, associated_token::authority = config, associated_token::token_program=token_program)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+impl HarvestWithheldFees<'_> {
+    /// Moves transfer fees withheld on the vault ATA into the mint, where the
+    /// mint's withdraw-withheld authority can claim them. They were never
+    /// credited to depositors, so vault accounting is unaffected.
+    pub fn harvest_withheld_fees(&mut self) -> Result<()> {
+        let cpi_accounts = HarvestWithheldTokensToMint {
+            token_program_id: self.token_program.to_account_info(),
+            mint: self.mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        harvest_withheld_tokens_to_mint(cpi_ctx, vec![self.vault.to_account_info()])
+    }
+}
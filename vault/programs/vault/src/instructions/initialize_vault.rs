@@ -1,30 +1,47 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{
+        token_metadata_initialize, Mint, TokenAccount, TokenInterface, TokenMetadataInitialize,
+    },
 };
 
-use crate::Config;
+use crate::{error::VaultError, utils::top_up_rent, Config, VaultInitialized};
 
 #[derive(Accounts)]
+#[instruction(decimals: u8)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
-    #[account(init, payer = admin, space = 8 + Config::INIT_SPACE, seeds = [b"config"], bump)]
+    #[account(init, payer = admin, space = 8 + Config::INIT_SPACE, seeds = [b"config", mint.key().as_ref()], bump)]
     pub config: Account<'info, Config>,
 
     #[account(
         init,
         payer = admin,
-        mint::authority = admin,
-        mint::decimals = 6,
+        mint::authority = config,
+        mint::decimals = decimals,
         mint::token_program = token_program,
         extensions::transfer_hook::authority = admin,
         extensions::transfer_hook::program_id = transfer_hook_program,
+        extensions::metadata_pointer::authority = config,
+        extensions::metadata_pointer::metadata_address = mint,
     )]
     pub mint: InterfaceAccount<'info, Mint>,
 
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"shares", config.key().as_ref()],
+        bump,
+        mint::authority = config,
+        mint::decimals = decimals,
+        mint::token_program = token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
     /// CHECK: this will be the program created for the whitelist tf hook
+    #[account(constraint = transfer_hook_program.executable @ VaultError::HookProgramNotExecutable)]
     pub transfer_hook_program: UncheckedAccount<'info>,
 
     #[account(init, payer = admin, associated_token::mint = mint, associated_token::authority = config)]
@@ -36,13 +53,56 @@ pub struct Initialize<'info> {
 }
 
 impl Initialize<'_> {
-    pub fn init_vault(&mut self, bumps: &InitializeBumps) -> Result<()> {
-        self.config.set_inner(Config {
-            admin: self.admin.key(),
-            vault: self.vault.key(),
-            mint: self.mint.key(),
-            bump: bumps.config,
+    pub fn init_vault(
+        &mut self,
+        decimals: u8,
+        name: String,
+        symbol: String,
+        uri: String,
+        bumps: &InitializeBumps,
+    ) -> Result<()> {
+        self.config.set_inner(Config::new(
+            self.admin.key(),
+            self.vault.key(),
+            self.mint.key(),
+            decimals,
+            self.transfer_hook_program.key(),
+            self.share_mint.key(),
+            bumps.config,
+        )?);
+
+        emit!(VaultInitialized {
+            config: self.config.key(),
+            admin: self.config.admin,
+            mint: self.config.mint,
+            share_mint: self.config.share_mint,
+            transfer_hook_program: self.config.transfer_hook_program,
+            decimals: self.config.decimals,
         });
-        Ok(())
+
+        let mint_key = self.mint.key();
+        let seeds: &[&[u8]] = &[b"config", mint_key.as_ref(), &[bumps.config]];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        // the metadata lives on the mint itself; the config PDA may update it
+        let cpi_accounts = TokenMetadataInitialize {
+            program_id: self.token_program.to_account_info(),
+            metadata: self.mint.to_account_info(),
+            update_authority: self.config.to_account_info(),
+            mint_authority: self.config.to_account_info(),
+            mint: self.mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_metadata_initialize(cpi_ctx, name, symbol, uri)?;
+
+        top_up_rent(
+            self.mint.to_account_info(),
+            self.admin.to_account_info(),
+            self.system_program.to_account_info(),
+        )
     }
 }
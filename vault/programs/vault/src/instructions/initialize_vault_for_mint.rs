@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{utils::transfer_hook_program_id, Config, VaultInitialized};
+
+/// Wraps a mint that already exists, owned by either SPL Token or Token-2022.
+/// The vault takes no authority over the mint itself.
+#[derive(Accounts)]
+pub struct InitializeForMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(init, payer = admin, space = 8 + Config::INIT_SPACE, seeds = [b"config", mint.key().as_ref()], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"shares", config.key().as_ref()],
+        bump,
+        mint::authority = config,
+        mint::decimals = mint.decimals,
+        mint::token_program = token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(init, payer = admin, associated_token::mint = mint, associated_token::authority = config, associated_token::token_program = token_program)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl InitializeForMint<'_> {
+    pub fn init_vault_for_mint(&mut self, bumps: &InitializeForMintBumps) -> Result<()> {
+        self.config.set_inner(Config::new(
+            self.admin.key(),
+            self.vault.key(),
+            self.mint.key(),
+            self.mint.decimals,
+            transfer_hook_program_id(&self.mint.to_account_info())?.unwrap_or_default(),
+            self.share_mint.key(),
+            bumps.config,
+        )?);
+
+        emit!(VaultInitialized {
+            config: self.config.key(),
+            admin: self.config.admin,
+            mint: self.config.mint,
+            share_mint: self.config.share_mint,
+            transfer_hook_program: self.config.transfer_hook_program,
+            decimals: self.config.decimals,
+        });
+        Ok(())
+    }
+}
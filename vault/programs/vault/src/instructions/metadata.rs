@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{
+        spl_token_metadata_interface::state::Field, token_metadata_update_field, Mint,
+        TokenMetadataUpdateField,
+    },
+};
+
+use crate::{error::VaultError, utils::top_up_rent, Config};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataField {
+    Name,
+    Symbol,
+    Uri,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = admin @ VaultError::NotAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl UpdateMetadata<'_> {
+    pub fn update_metadata(&mut self, field: MetadataField, value: String) -> Result<()> {
+        let field = match field {
+            MetadataField::Name => Field::Name,
+            MetadataField::Symbol => Field::Symbol,
+            MetadataField::Uri => Field::Uri,
+        };
+
+        let mint_key = self.mint.key();
+        let seeds: &[&[u8]] = &[b"config", mint_key.as_ref(), &[self.config.bump]];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        let cpi_accounts = TokenMetadataUpdateField {
+            program_id: self.token_program.to_account_info(),
+            metadata: self.mint.to_account_info(),
+            update_authority: self.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_metadata_update_field(cpi_ctx, field, value)?;
+
+        // a longer value grows the mint account
+        top_up_rent(
+            self.mint.to_account_info(),
+            self.admin.to_account_info(),
+            self.system_program.to_account_info(),
+        )
+    }
+}
@@ -1,26 +1,35 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_2022::{mint_to, MintTo},
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
 };
 
+use crate::{error::VaultError, Config, Minted, PAUSE_MINT};
+
 #[derive(Accounts)]
 pub struct MintToken<'info> {
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub minter: Signer<'info>,
     pub user: SystemAccount<'info>,
 
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump = config.bump,
+        constraint = config.is_minter(&minter.key()) @ VaultError::NotMinter,
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(
         mut,
-        mint::decimals = 6,
+        mint::authority = config,
+        mint::decimals = config.decimals,
         mint::token_program = token_program
     )]
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
-        init,
-        payer=admin,
+        init_if_needed,
+        payer=minter,
         associated_token::mint=mint,
         associated_token::authority=user,
         associated_token::token_program=token_program
@@ -34,17 +43,124 @@ pub struct MintToken<'info> {
 
 impl MintToken<'_> {
     pub fn mint_token(&mut self, amount: u64) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
+        require!(!self.config.is_paused(PAUSE_MINT), VaultError::Paused);
+        check_max_supply(&self.config, &self.mint, amount)?;
+
+        mint_with_config(
+            &self.config,
+            &self.mint,
+            self.user_ata.to_account_info(),
+            &self.token_program,
+            amount,
+        )?;
+
+        emit!(Minted {
+            config: self.config.key(),
+            minter: self.minter.key(),
+            token_account: self.user_ata.key(),
+            amount,
+        });
+        Ok(())
+    }
+}
 
-        let cpi_accounts = MintTo {
-            to: self.user_ata.to_account_info(),
-            mint: self.mint.to_account_info(),
-            authority: self.admin.to_account_info(),
-        };
+#[derive(Accounts)]
+pub struct BatchMint<'info> {
+    pub minter: Signer<'info>,
 
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump = config.bump,
+        constraint = config.is_minter(&minter.key()) @ VaultError::NotMinter,
+    )]
+    pub config: Account<'info, Config>,
 
-        mint_to(cpi_ctx, amount)?;
+    #[account(
+        mut,
+        mint::authority = config,
+        mint::decimals = config.decimals,
+        mint::token_program = token_program
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> BatchMint<'info> {
+    /// Mints `amounts[i]` to the i-th remaining account, each an existing token
+    /// account of this mint.
+    pub fn batch_mint(
+        &mut self,
+        amounts: Vec<u64>,
+        recipients: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_MINT), VaultError::Paused);
+        require!(
+            !amounts.is_empty() && amounts.len() == recipients.len(),
+            VaultError::BatchLengthMismatch
+        );
+
+        let total = amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(VaultError::MathOverflow)?;
+        check_max_supply(&self.config, &self.mint, total)?;
+
+        for (recipient, amount) in recipients.iter().zip(amounts) {
+            let token_account = InterfaceAccount::<TokenAccount>::try_from(recipient)?;
+            require_keys_eq!(
+                token_account.mint,
+                self.mint.key(),
+                VaultError::InvalidRecipient
+            );
+
+            mint_with_config(
+                &self.config,
+                &self.mint,
+                recipient.clone(),
+                &self.token_program,
+                amount,
+            )?;
+
+            emit!(Minted {
+                config: self.config.key(),
+                minter: self.minter.key(),
+                token_account: recipient.key(),
+                amount,
+            });
+        }
         Ok(())
     }
 }
+
+fn check_max_supply(config: &Config, mint: &Mint, amount: u64) -> Result<()> {
+    let supply = mint
+        .supply
+        .checked_add(amount)
+        .ok_or(VaultError::MathOverflow)?;
+    require!(supply <= config.max_supply, VaultError::MaxSupplyExceeded);
+    Ok(())
+}
+
+/// Mints with the config PDA, the mint authority, as signer.
+fn mint_with_config<'info>(
+    config: &Account<'info, Config>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let mint_key = mint.key();
+    let seeds: &[&[u8]] = &[b"config", mint_key.as_ref(), &[config.bump]];
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+    let cpi_accounts = MintTo {
+        to,
+        mint: mint.to_account_info(),
+        authority: config.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+    mint_to(cpi_ctx, amount)
+}
@@ -1,9 +1,27 @@
+pub mod admin_operations;
+pub mod close_position;
 pub mod deposit;
+pub mod fees;
 pub mod initialize_vault;
+pub mod initialize_vault_for_mint;
+pub mod metadata;
 pub mod mint;
+pub mod rewards;
+pub mod set_pause;
+pub mod stake;
 pub mod withdraw;
+pub mod withdraw_queue;
 
+pub use admin_operations::*;
+pub use close_position::*;
 pub use deposit::*;
+pub use fees::*;
 pub use initialize_vault::*;
+pub use initialize_vault_for_mint::*;
+pub use metadata::*;
 pub use mint::*;
+pub use rewards::*;
+pub use set_pause::*;
+pub use stake::*;
 pub use withdraw::*;
+pub use withdraw_queue::*;
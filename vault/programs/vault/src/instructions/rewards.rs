@@ -0,0 +1,181 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{error::VaultError, Amount, Config, LockExpired, BPS_DENOMINATOR};
+
+#[derive(Accounts)]
+pub struct ConfigureRewards<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", config.mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ VaultError::NotAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mint::token_program = reward_token_program,
+        constraint = reward_mint.key() != config.mint @ VaultError::RewardMintIsVaultMint,
+        constraint = reward_mint.key() != config.share_mint @ VaultError::RewardMintIsShareMint,
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+        associated_token::token_program = reward_token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl ConfigureRewards<'_> {
+    /// Sets the emission rate. The reward vault is funded by plain transfers to
+    /// the config's reward ATA.
+    pub fn configure_rewards(&mut self, reward_rate: u64) -> Result<()> {
+        require!(
+            self.config.reward_mint == Pubkey::default()
+                || self.config.reward_mint == self.reward_mint.key(),
+            VaultError::RewardMintMismatch
+        );
+
+        // everything up to now is emitted at the old rate
+        self.config
+            .update_rewards(Clock::get()?.unix_timestamp)
+            .ok_or(VaultError::MathOverflow)?;
+
+        self.config.reward_mint = self.reward_mint.key();
+        self.config.reward_rate = reward_rate;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, seeds = [b"config", config.mint.as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds=[b"amount", config.key().as_ref(), user.key().as_ref()],
+        bump=amount_pda.bump,
+    )]
+    pub amount_pda: Account<'info, Amount>,
+
+    #[account(address = config.reward_mint, mint::token_program = reward_token_program)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = config,
+        associated_token::token_program = reward_token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+        associated_token::token_program = reward_token_program
+    )]
+    pub user_reward_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl ClaimRewards<'_> {
+    pub fn claim_rewards(&mut self) -> Result<()> {
+        self.config
+            .sync_position(&mut self.amount_pda, Clock::get()?.unix_timestamp)
+            .ok_or(VaultError::MathOverflow)?;
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
+
+        // an underfunded reward vault pays what it can, the rest stays pending
+        let payout = self
+            .amount_pda
+            .pending_rewards
+            .min(self.reward_vault.amount);
+        require!(payout > 0, VaultError::NoRewardsToClaim);
+        self.amount_pda.pending_rewards -= payout;
+
+        let mint_key = self.config.mint;
+        let seeds: &[&[u8]] = &[b"config", mint_key.as_ref(), &[self.config.bump]];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        let cpi_accounts = TransferChecked {
+            from: self.reward_vault.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.user_reward_ata.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.reward_token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(cpi_ctx, payout, self.reward_mint.decimals)
+    }
+}
+
+/// Drops the multiplier of a lock that has expired. Anyone may call it, so a
+/// boost stops earning once `locked_until` passes even if the owner stays idle.
+#[derive(Accounts)]
+pub struct ExpireLock<'info> {
+    #[account(mut, seeds = [b"config", config.mint.as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: only used to derive the position's address
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"amount", config.key().as_ref(), owner.key().as_ref()],
+        bump=amount_pda.bump,
+    )]
+    pub amount_pda: Account<'info, Amount>,
+}
+
+impl ExpireLock<'_> {
+    pub fn expire_lock(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(!self.amount_pda.is_locked(now), VaultError::PositionLocked);
+        require!(
+            self.amount_pda.multiplier_bps != BPS_DENOMINATOR,
+            VaultError::NoLockToExpire
+        );
+
+        self.config
+            .sync_position(&mut self.amount_pda, now)
+            .ok_or(VaultError::MathOverflow)?;
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
+
+        emit!(LockExpired {
+            config: self.config.key(),
+            user: self.owner.key(),
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::VaultError, Config, PAUSE_ALL};
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", config.mint.as_ref()],
+        bump = config.bump,
+        constraint = authority.key() == config.admin || authority.key() == config.pauser @ VaultError::NotPauser,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl SetPause<'_> {
+    /// Replaces the pause bitmask; pass `0` to resume every operation.
+    pub fn set_pause(&mut self, paused: u8) -> Result<()> {
+        require!(paused & !PAUSE_ALL == 0, VaultError::InvalidPauseFlags);
+
+        self.config.paused = paused;
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{error::VaultError, Amount, Config, Staked, Unstaked, PAUSE_DEPOSIT, PAUSE_WITHDRAW};
+
+/// Moves shares from the user's wallet into their position, e.g. shares bought
+/// or received from another holder. Only staked shares earn rewards or can be
+/// withdrawn.
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, seeds = [b"config", config.mint.as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer=user,
+        seeds=[b"amount", config.key().as_ref(), user.key().as_ref()],
+        bump,
+        space = 8 + Amount::INIT_SPACE
+    )]
+    pub amount_pda: Account<'info, Amount>,
+
+    #[account(address = config.share_mint, mint::token_program = token_program)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Prices the staked shares for the per-user deposit cap.
+    #[account(address = config.vault)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint=share_mint,
+        associated_token::authority=user,
+        associated_token::token_program=token_program
+    )]
+    pub user_shares: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer=user,
+        associated_token::mint=share_mint,
+        associated_token::authority=config,
+        associated_token::token_program=token_program
+    )]
+    pub share_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl Stake<'_> {
+    pub fn stake(&mut self, shares: u64, bumps: &StakeBumps) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_DEPOSIT), VaultError::Paused);
+        require!(shares > 0, VaultError::ZeroShares);
+
+        let now = Clock::get()?.unix_timestamp;
+        self.config
+            .sync_position(&mut self.amount_pda, now)
+            .ok_or(VaultError::MathOverflow)?;
+
+        // staked shares count towards the cap at what they are worth now
+        let total_assets = self
+            .config
+            .total_assets(self.vault.amount)
+            .ok_or(VaultError::MathOverflow)?;
+        let value = Config::convert_to_assets(shares, total_assets, self.share_mint.supply)
+            .ok_or(VaultError::MathOverflow)?;
+        let deposited = self
+            .amount_pda
+            .deposited
+            .checked_add(value)
+            .ok_or(VaultError::MathOverflow)?;
+        require!(
+            deposited <= self.config.max_per_user,
+            VaultError::UserDepositCapExceeded
+        );
+
+        let cpi_accounts = TransferChecked {
+            from: self.user_shares.to_account_info(),
+            mint: self.share_mint.to_account_info(),
+            to: self.share_escrow.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        transfer_checked(cpi_ctx, shares, self.share_mint.decimals)?;
+
+        self.amount_pda.shares = self
+            .amount_pda
+            .shares
+            .checked_add(shares)
+            .ok_or(VaultError::MathOverflow)?;
+        self.amount_pda.deposited = deposited;
+        self.amount_pda.bump = bumps.amount_pda;
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
+
+        emit!(Staked {
+            config: self.config.key(),
+            user: self.user.key(),
+            shares,
+        });
+        Ok(())
+    }
+}
+
+/// Moves shares out of the user's position into their wallet, where they stop
+/// earning rewards but can be transferred freely. Locked shares stay in escrow
+/// until the lock expires, and unstaked shares still count towards the
+/// per-user deposit cap.
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, seeds = [b"config", config.mint.as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds=[b"amount", config.key().as_ref(), user.key().as_ref()],
+        bump=amount_pda.bump,
+    )]
+    pub amount_pda: Account<'info, Amount>,
+
+    #[account(address = config.share_mint, mint::token_program = token_program)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer=user,
+        associated_token::mint=share_mint,
+        associated_token::authority=user,
+        associated_token::token_program=token_program
+    )]
+    pub user_shares: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint=share_mint,
+        associated_token::authority=config,
+        associated_token::token_program=token_program
+    )]
+    pub share_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl Unstake<'_> {
+    pub fn unstake(&mut self, shares: u64) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_WITHDRAW), VaultError::Paused);
+        require!(shares > 0, VaultError::ZeroShares);
+        require!(
+            shares <= self.amount_pda.shares,
+            VaultError::AmountExceededUrDeposit
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(!self.amount_pda.is_locked(now), VaultError::PositionLocked);
+
+        self.config
+            .sync_position(&mut self.amount_pda, now)
+            .ok_or(VaultError::MathOverflow)?;
+
+        let mint_key = self.config.mint;
+        let seeds: &[&[u8]] = &[b"config", mint_key.as_ref(), &[self.config.bump]];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        let cpi_accounts = TransferChecked {
+            from: self.share_escrow.to_account_info(),
+            mint: self.share_mint.to_account_info(),
+            to: self.user_shares.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        transfer_checked(cpi_ctx, shares, self.share_mint.decimals)?;
+
+        self.amount_pda.shares -= shares;
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
+
+        emit!(Unstaked {
+            config: self.config.key(),
+            user: self.user.key(),
+            shares,
+        });
+        Ok(())
+    }
+}
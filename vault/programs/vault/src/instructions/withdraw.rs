@@ -1,12 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface},
 };
 
 use spl_token_2022::onchain::invoke_transfer_checked;
 
-use crate::{error::VaultError, Amount, Config};
+use crate::{error::VaultError, utils::hook_accounts, Amount, Config, Withdrawn, PAUSE_WITHDRAW};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
@@ -15,17 +15,16 @@ pub struct Withdraw<'info> {
 
     #[account(
         mut,
-        // close=user, have a separate ixn for closing this pda
-        seeds=[b"amount", user.key().as_ref()],
+        seeds=[b"amount", config.key().as_ref(), user.key().as_ref()],
         bump=amount_pda.bump,
     )]
     pub amount_pda: Account<'info, Amount>,
 
-    #[account( seeds = [b"config"], bump=config.bump)]
+    #[account(mut, seeds = [b"config", mint.key().as_ref()], bump = config.bump)]
     pub config: Account<'info, Config>,
 
     #[account(
-        mint::decimals = 6,
+        mint::decimals = config.decimals,
         mint::token_program = token_program,
     )]
     pub mint: InterfaceAccount<'info, Mint>,
@@ -41,51 +40,172 @@ pub struct Withdraw<'info> {
     #[account(mut, associated_token::mint = mint, associated_token::authority = config, associated_token::token_program=token_program)]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: ExtraAccountMetalist Account
-    pub extra_account_meta_list: UncheckedAccount<'info>,
-    /// CHECK: ExtraAccountMetalist Account
-    pub whitelist: UncheckedAccount<'info>,
-    /// CHECK: this will be the program created for the whitelist tf hook
-    pub transfer_hook_program: UncheckedAccount<'info>,
+    #[account(mut, address = config.share_mint)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint=share_mint,
+        associated_token::authority=config,
+        associated_token::token_program=token_program
+    )]
+    pub share_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-impl Withdraw<'_> {
-    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
-        let user_deposited_amount = self.amount_pda.amount;
+impl<'info> Withdraw<'info> {
+    pub fn withdraw(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_WITHDRAW), VaultError::Paused);
+        require!(
+            self.config.withdraw_cooldown == 0,
+            VaultError::CooldownActive
+        );
 
+        let now = Clock::get()?.unix_timestamp;
+        require!(!self.amount_pda.is_locked(now), VaultError::PositionLocked);
+        self.sync_rewards(now)?;
+
+        let total_assets = self
+            .config
+            .total_assets(self.vault.amount)
+            .ok_or(VaultError::MathOverflow)?;
+        // nothing to redeem against, or more shares than could ever be held
+        let shares = Config::convert_to_shares_ceil(amount, total_assets, self.share_mint.supply)
+            .ok_or(VaultError::AmountExceededUrDeposit)?;
+        require!(shares > 0, VaultError::ZeroShares);
         require!(
-            user_deposited_amount <= amount,
+            shares <= self.amount_pda.shares,
             VaultError::AmountExceededUrDeposit
         );
+        self.redeem(shares, amount, remaining_accounts)?;
 
-        let seeds: &[&[u8]] = &[b"config", &[self.config.bump]];
+        self.amount_pda.shares -= shares;
+        self.amount_pda.deposited = self.amount_pda.deposited.saturating_sub(amount);
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Redeems every share in the position, including any yield accrued to them.
+    pub fn withdraw_all(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_WITHDRAW), VaultError::Paused);
+        require!(
+            self.config.withdraw_cooldown == 0,
+            VaultError::CooldownActive
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(!self.amount_pda.is_locked(now), VaultError::PositionLocked);
+        self.sync_rewards(now)?;
+
+        let shares = self.amount_pda.shares;
+        require!(shares > 0, VaultError::AmountExceededUrDeposit);
+
+        let total_assets = self
+            .config
+            .total_assets(self.vault.amount)
+            .ok_or(VaultError::MathOverflow)?;
+        let amount = Config::convert_to_assets(shares, total_assets, self.share_mint.supply)
+            .ok_or(VaultError::MathOverflow)?;
+        self.redeem(shares, amount, remaining_accounts)?;
+
+        self.amount_pda.shares = 0;
+        self.amount_pda.deposited = 0;
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Pays out shares that `request_withdraw` already took out of the position,
+    /// at the current share price. `claim_withdraw` enforces the cooldown.
+    pub fn redeem_queued(
+        &mut self,
+        shares: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_WITHDRAW), VaultError::Paused);
+
+        let total_assets = self
+            .config
+            .total_assets(self.vault.amount)
+            .ok_or(VaultError::MathOverflow)?;
+        let amount = Config::convert_to_assets(shares, total_assets, self.share_mint.supply)
+            .ok_or(VaultError::MathOverflow)?;
+        self.redeem(shares, amount, remaining_accounts)?;
+
+        self.amount_pda.deposited = self.amount_pda.deposited.saturating_sub(amount);
+        Ok(())
+    }
+
+    fn sync_rewards(&mut self, now: i64) -> Result<()> {
+        self.config
+            .sync_position(&mut self.amount_pda, now)
+            .ok_or(VaultError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Burns `shares` from the escrow and pays out `amount` less the withdraw fee, which stays in
+    /// the vault until collected.
+    fn redeem(
+        &mut self,
+        shares: u64,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let fee = Config::fee(amount, self.config.withdraw_fee_bps);
+        self.config.accrued_fees = self
+            .config
+            .accrued_fees
+            .checked_add(fee)
+            .ok_or(VaultError::MathOverflow)?;
+
+        let mint_key = self.mint.key();
+        let seeds: &[&[u8]] = &[b"config", mint_key.as_ref(), &[self.config.bump]];
         let signer_seeds: &[&[&[u8]]] = &[seeds];
 
+        let cpi_accounts = Burn {
+            mint: self.share_mint.to_account_info(),
+            from: self.share_escrow.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        burn(cpi_ctx, shares)?;
+
         invoke_transfer_checked(
             &self.token_program.key(),
             self.vault.to_account_info(),
             self.mint.to_account_info(),
             self.user_ata.to_account_info(),
             self.config.to_account_info(),
-            &[
-                self.extra_account_meta_list.to_account_info(),
-                self.whitelist.to_account_info(),
-                self.transfer_hook_program.to_account_info(),
-            ],
-            amount,
+            hook_accounts(
+                &self.mint.to_account_info(),
+                self.config.transfer_hook_program,
+                remaining_accounts,
+            )?,
+            amount - fee,
             self.mint.decimals,
             signer_seeds,
         )?;
 
-        self.amount_pda.amount = self
-            .amount_pda
-            .amount
-            .checked_sub(amount)
-            .expect("Incorrect withdraw amount");
+        emit!(Withdrawn {
+            config: self.config.key(),
+            user: self.user.key(),
+            amount,
+            fee,
+            shares,
+        });
         Ok(())
     }
 }
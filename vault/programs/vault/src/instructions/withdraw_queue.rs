@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+
+use super::withdraw::*;
+use crate::{error::VaultError, Amount, Config, PendingWithdrawal, PAUSE_WITHDRAW};
+
+#[derive(Accounts)]
+pub struct RequestWithdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, seeds = [b"config", config.mint.as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds=[b"amount", config.key().as_ref(), user.key().as_ref()],
+        bump=amount_pda.bump,
+    )]
+    pub amount_pda: Account<'info, Amount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + PendingWithdrawal::INIT_SPACE,
+        seeds = [b"withdrawal", config.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl RequestWithdraw<'_> {
+    /// Moves `shares` out of the position into the request, so they stop
+    /// earning rewards and cannot be withdrawn or unstaked in the meantime.
+    pub fn request_withdraw(&mut self, shares: u64, bumps: &RequestWithdrawBumps) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_WITHDRAW), VaultError::Paused);
+        require!(shares > 0, VaultError::ZeroShares);
+        require!(
+            shares <= self.amount_pda.shares,
+            VaultError::AmountExceededUrDeposit
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(!self.amount_pda.is_locked(now), VaultError::PositionLocked);
+
+        self.config
+            .sync_position(&mut self.amount_pda, now)
+            .ok_or(VaultError::MathOverflow)?;
+        self.amount_pda.shares -= shares;
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
+
+        let unlock_ts = now
+            .checked_add(self.config.withdraw_cooldown)
+            .ok_or(VaultError::MathOverflow)?;
+
+        self.pending_withdrawal.set_inner(PendingWithdrawal {
+            shares,
+            unlock_ts,
+            bump: bumps.pending_withdrawal,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimWithdraw<'info> {
+    pub withdraw: Withdraw<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"withdrawal", withdraw.config.key().as_ref(), withdraw.user.key().as_ref()],
+        bump = pending_withdrawal.bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+}
+
+impl<'info> ClaimWithdraw<'info> {
+    pub fn claim_withdraw(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.pending_withdrawal.unlock_ts,
+            VaultError::CooldownNotElapsed
+        );
+
+        self.withdraw
+            .redeem_queued(self.pending_withdrawal.shares, remaining_accounts)?;
+
+        self.pending_withdrawal
+            .close(self.withdraw.user.to_account_info())?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, seeds = [b"config", config.mint.as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds=[b"amount", config.key().as_ref(), user.key().as_ref()],
+        bump=amount_pda.bump,
+    )]
+    pub amount_pda: Account<'info, Amount>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"withdrawal", config.key().as_ref(), user.key().as_ref()],
+        bump = pending_withdrawal.bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+}
+
+impl CancelWithdraw<'_> {
+    /// Returns the requested shares to the position.
+    pub fn cancel_withdraw(&mut self) -> Result<()> {
+        self.config
+            .sync_position(&mut self.amount_pda, Clock::get()?.unix_timestamp)
+            .ok_or(VaultError::MathOverflow)?;
+        self.amount_pda.shares = self
+            .amount_pda
+            .shares
+            .checked_add(self.pending_withdrawal.shares)
+            .ok_or(VaultError::MathOverflow)?;
+        self.config
+            .restake(&mut self.amount_pda)
+            .ok_or(VaultError::MathOverflow)?;
+        Ok(())
+    }
+}
@@ -1,12 +1,15 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod tests;
+pub mod utils;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -16,17 +19,153 @@ declare_id!("6Ttxuh7AqpLQMkVS9xCAu6ncbsyFmq8f3EZVhskoQ3m4");
 pub mod vault {
     use super::*;
 
-    pub fn initialize_vault(ctx: Context<Initialize>) -> Result<()> {
-        ctx.accounts.init_vault(&ctx.bumps)
+    pub fn initialize_vault(
+        ctx: Context<Initialize>,
+        decimals: u8,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .init_vault(decimals, name, symbol, uri, &ctx.bumps)
+    }
+    pub fn initialize_vault_for_mint(ctx: Context<InitializeForMint>) -> Result<()> {
+        ctx.accounts.init_vault_for_mint(&ctx.bumps)
     }
 
     pub fn mint(ctx: Context<MintToken>, amount: u64) -> Result<()> {
         ctx.accounts.mint_token(amount)
     }
-    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-        ctx.accounts.withdraw(amount)
+    pub fn batch_mint<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchMint<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        ctx.accounts.batch_mint(amounts, ctx.remaining_accounts)
+    }
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw(amount, ctx.remaining_accounts)
+    }
+    pub fn withdraw_all<'info>(ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>) -> Result<()> {
+        ctx.accounts.withdraw_all(ctx.remaining_accounts)
+    }
+    pub fn request_withdraw(ctx: Context<RequestWithdraw>, shares: u64) -> Result<()> {
+        ctx.accounts.request_withdraw(shares, &ctx.bumps)
+    }
+    pub fn claim_withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimWithdraw<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.claim_withdraw(ctx.remaining_accounts)
+    }
+    pub fn cancel_withdraw(ctx: Context<CancelWithdraw>) -> Result<()> {
+        ctx.accounts.cancel_withdraw()
+    }
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .deposit(amount, &ctx.bumps, ctx.remaining_accounts)
+    }
+    pub fn deposit_locked<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+        amount: u64,
+        tier: u8,
+    ) -> Result<()> {
+        ctx.accounts
+            .deposit_locked(amount, tier, &ctx.bumps, ctx.remaining_accounts)
+    }
+    pub fn stake(ctx: Context<Stake>, shares: u64) -> Result<()> {
+        ctx.accounts.stake(shares, &ctx.bumps)
+    }
+    pub fn unstake(ctx: Context<Unstake>, shares: u64) -> Result<()> {
+        ctx.accounts.unstake(shares)
+    }
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        ctx.accounts.close_position()
+    }
+
+    pub fn propose_admin(ctx: Context<AdminOperations>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.propose_admin(new_admin)
+    }
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        ctx.accounts.accept_admin()
+    }
+    pub fn set_role(ctx: Context<AdminOperations>, role: Role, account: Pubkey) -> Result<()> {
+        ctx.accounts.set_role(role, account)
+    }
+    pub fn add_minter(ctx: Context<AdminOperations>, minter: Pubkey) -> Result<()> {
+        ctx.accounts.add_minter(minter)
+    }
+    pub fn remove_minter(ctx: Context<AdminOperations>, minter: Pubkey) -> Result<()> {
+        ctx.accounts.remove_minter(minter)
+    }
+    pub fn set_max_supply(ctx: Context<AdminOperations>, max_supply: u64) -> Result<()> {
+        ctx.accounts.set_max_supply(max_supply)
+    }
+    pub fn set_deposit_limits(
+        ctx: Context<AdminOperations>,
+        max_total_deposits: u64,
+        max_per_user: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_deposit_limits(max_total_deposits, max_per_user)
+    }
+    pub fn set_withdraw_cooldown(
+        ctx: Context<AdminOperations>,
+        withdraw_cooldown: i64,
+    ) -> Result<()> {
+        ctx.accounts.set_withdraw_cooldown(withdraw_cooldown)
+    }
+    pub fn set_lockup_tier(
+        ctx: Context<AdminOperations>,
+        index: u8,
+        duration: i64,
+        multiplier_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_lockup_tier(index, duration, multiplier_bps)
+    }
+
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadata>,
+        field: MetadataField,
+        value: String,
+    ) -> Result<()> {
+        ctx.accounts.update_metadata(field, value)
+    }
+
+    pub fn set_pause(ctx: Context<SetPause>, paused: u8) -> Result<()> {
+        ctx.accounts.set_pause(paused)
+    }
+
+    pub fn set_fees(
+        ctx: Context<FeeOperations>,
+        deposit_fee_bps: u16,
+        withdraw_fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_fees(deposit_fee_bps, withdraw_fee_bps, treasury)
+    }
+    pub fn collect_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectFees<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.collect_fees(ctx.remaining_accounts)
+    }
+    pub fn harvest_withheld_fees(ctx: Context<HarvestWithheldFees>) -> Result<()> {
+        ctx.accounts.harvest_withheld_fees()
+    }
+
+    pub fn configure_rewards(ctx: Context<ConfigureRewards>, reward_rate: u64) -> Result<()> {
+        ctx.accounts.configure_rewards(reward_rate)
+    }
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        ctx.accounts.claim_rewards()
     }
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        ctx.accounts.deposit(amount, &ctx.bumps)
+    pub fn expire_lock(ctx: Context<ExpireLock>) -> Result<()> {
+        ctx.accounts.expire_lock()
     }
 }
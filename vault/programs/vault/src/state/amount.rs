@@ -1,8 +1,37 @@
 use anchor_lang::prelude::*;
 
+use crate::REWARD_PRECISION;
+
 #[account]
 #[derive(InitSpace)]
 pub struct Amount {
-    pub amount: u64,
+    /// Shares held for this position in the config's share escrow.
+    pub shares: u64,
+    /// Assets put into the position net of fees, checked against `max_per_user`.
+    /// Only withdrawals lower it; unstaking shares to the wallet does not.
+    pub deposited: u64,
+    /// Unix timestamp before which nothing can be withdrawn; zero when unlocked.
+    pub locked_until: i64,
+    /// Reward multiplier earned by the current lock, in basis points (10_000 = 1x).
+    pub multiplier_bps: u16,
+    /// `shares` weighted by `multiplier_bps`; this is what earns rewards.
+    pub stake: u64,
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
     pub bump: u8,
 }
+
+impl Amount {
+    pub fn is_locked(&self, now: i64) -> bool {
+        now < self.locked_until
+    }
+
+    /// Moves rewards earned by the current stake since the last sync into
+    /// `pending_rewards`. Call `Config::restake` afterwards to reset the debt.
+    pub fn settle_rewards(&mut self, acc_reward_per_share: u128) -> Option<()> {
+        let earned = (self.stake as u128).checked_mul(acc_reward_per_share)? / REWARD_PRECISION;
+        let owed = u64::try_from(earned.checked_sub(self.reward_debt)?).ok()?;
+        self.pending_rewards = self.pending_rewards.checked_add(owed)?;
+        Some(())
+    }
+}
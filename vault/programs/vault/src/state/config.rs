@@ -1,10 +1,201 @@
 use anchor_lang::prelude::*;
 
+use crate::{Amount, BPS_DENOMINATOR, MAX_LOCKUP_TIERS, MAX_MINTERS, REWARD_PRECISION};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
     pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    /// Accounts allowed to mint through the vault; `Pubkey::default()` marks a free slot.
+    pub minters: [Pubkey; MAX_MINTERS],
+    pub pauser: Pubkey,
+    pub fee_manager: Pubkey,
     pub vault: Pubkey,
     pub mint: Pubkey,
+    pub decimals: u8,
+    /// Cap on the mint's total supply enforced by `mint`.
+    pub max_supply: u64,
+    /// Hook program the mint was set up with; `Pubkey::default()` for mints without one.
+    pub transfer_hook_program: Pubkey,
+    pub share_mint: Pubkey,
+    pub paused: u8,
+    pub max_total_deposits: u64,
+    pub max_per_user: u64,
+    pub deposit_fee_bps: u16,
+    pub withdraw_fee_bps: u16,
+    pub treasury: Pubkey,
+    /// Fees held in the vault ATA until `collect_fees`; not backing any shares.
+    pub accrued_fees: u64,
+    /// Seconds between `request_withdraw` and `claim_withdraw`; zero allows instant withdrawals.
+    pub withdraw_cooldown: i64,
+    pub lockup_tiers: [LockupTier; MAX_LOCKUP_TIERS],
+    /// `Pubkey::default()` until `configure_rewards` is called.
+    pub reward_mint: Pubkey,
+    /// Reward tokens emitted per second across all stakers.
+    pub reward_rate: u64,
+    pub acc_reward_per_share: u128,
+    pub last_reward_ts: i64,
+    pub total_stake: u64,
     pub bump: u8,
 }
+
+/// A lock duration users can opt into with `deposit_locked`. A zero duration
+/// marks the tier as disabled.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default)]
+pub struct LockupTier {
+    pub duration: i64,
+    pub multiplier_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Pauser,
+    FeeManager,
+}
+
+impl Config {
+    /// A fresh vault with every role held by `admin`, no caps, fees or rewards.
+    pub fn new(
+        admin: Pubkey,
+        vault: Pubkey,
+        mint: Pubkey,
+        decimals: u8,
+        transfer_hook_program: Pubkey,
+        share_mint: Pubkey,
+        bump: u8,
+    ) -> Result<Self> {
+        let mut minters = [Pubkey::default(); MAX_MINTERS];
+        minters[0] = admin;
+
+        Ok(Self {
+            admin,
+            pending_admin: None,
+            minters,
+            pauser: admin,
+            fee_manager: admin,
+            vault,
+            mint,
+            decimals,
+            max_supply: u64::MAX,
+            transfer_hook_program,
+            share_mint,
+            paused: 0,
+            max_total_deposits: u64::MAX,
+            max_per_user: u64::MAX,
+            deposit_fee_bps: 0,
+            withdraw_fee_bps: 0,
+            treasury: Pubkey::default(),
+            accrued_fees: 0,
+            withdraw_cooldown: 0,
+            lockup_tiers: [LockupTier::default(); MAX_LOCKUP_TIERS],
+            reward_mint: Pubkey::default(),
+            reward_rate: 0,
+            acc_reward_per_share: 0,
+            last_reward_ts: Clock::get()?.unix_timestamp,
+            total_stake: 0,
+            bump,
+        })
+    }
+
+    pub fn is_minter(&self, account: &Pubkey) -> bool {
+        *account != Pubkey::default() && self.minters.contains(account)
+    }
+
+    pub fn is_paused(&self, operation: u8) -> bool {
+        self.paused & operation != 0
+    }
+
+    /// Accrues emissions since `last_reward_ts` into `acc_reward_per_share`.
+    pub fn update_rewards(&mut self, now: i64) -> Option<()> {
+        if now <= self.last_reward_ts {
+            return Some(());
+        }
+        if self.total_stake > 0 && self.reward_rate > 0 {
+            let elapsed = (now - self.last_reward_ts) as u128;
+            let emitted = elapsed.checked_mul(self.reward_rate as u128)?;
+            self.acc_reward_per_share = self
+                .acc_reward_per_share
+                .checked_add(emitted.checked_mul(REWARD_PRECISION)? / self.total_stake as u128)?;
+        }
+        self.last_reward_ts = now;
+        Some(())
+    }
+
+    /// Accrues rewards up to `now` into the position. A boosted multiplier
+    /// only earns until the lock that granted it expires, after which the
+    /// position is restaked at 1x. Accrual that the accumulator already
+    /// recorded past `locked_until` cannot be split out and stays boosted,
+    /// which is why `expire_lock` lets anyone run this as soon as the lock ends.
+    pub fn sync_position(&mut self, position: &mut Amount, now: i64) -> Option<()> {
+        if position.multiplier_bps != BPS_DENOMINATOR && !position.is_locked(now) {
+            self.update_rewards(position.locked_until)?;
+            position.settle_rewards(self.acc_reward_per_share)?;
+            position.multiplier_bps = BPS_DENOMINATOR;
+            self.restake(position)?;
+        }
+        self.update_rewards(now)?;
+        position.settle_rewards(self.acc_reward_per_share)
+    }
+
+    /// Recomputes a position's weighted stake after its shares or multiplier
+    /// changed, keeping `total_stake` and the position's reward debt in step.
+    pub fn restake(&mut self, position: &mut Amount) -> Option<()> {
+        let stake = u64::try_from(
+            (position.shares as u128) * (position.multiplier_bps as u128)
+                / (BPS_DENOMINATOR as u128),
+        )
+        .ok()?;
+        self.total_stake = self
+            .total_stake
+            .checked_sub(position.stake)?
+            .checked_add(stake)?;
+        position.stake = stake;
+        position.reward_debt =
+            (stake as u128).checked_mul(self.acc_reward_per_share)? / REWARD_PRECISION;
+        Some(())
+    }
+
+    /// Vault balance that belongs to shareholders, i.e. excluding uncollected fees.
+    pub fn total_assets(&self, vault_balance: u64) -> Option<u64> {
+        vault_balance.checked_sub(self.accrued_fees)
+    }
+
+    pub fn fee(amount: u64, fee_bps: u16) -> u64 {
+        // fee_bps is capped below BPS_DENOMINATOR, so the result always fits
+        ((amount as u128) * (fee_bps as u128) / (BPS_DENOMINATOR as u128)) as u64
+    }
+
+    /// Shares owed for depositing `assets` into a vault holding `total_assets`
+    /// against `total_shares` outstanding. Rounds down, in favour of the vault.
+    pub fn convert_to_shares(assets: u64, total_assets: u64, total_shares: u64) -> Option<u64> {
+        if total_shares == 0 || total_assets == 0 {
+            return Some(assets);
+        }
+        u64::try_from((assets as u128) * (total_shares as u128) / (total_assets as u128)).ok()
+    }
+
+    /// Shares that must be burned to take `assets` out of the vault. Rounds up,
+    /// in favour of the vault. `None` when there are no shares or no assets to
+    /// redeem against.
+    pub fn convert_to_shares_ceil(
+        assets: u64,
+        total_assets: u64,
+        total_shares: u64,
+    ) -> Option<u64> {
+        if total_shares == 0 || total_assets == 0 {
+            return None;
+        }
+        let numerator = (assets as u128) * (total_shares as u128);
+        u64::try_from(numerator.div_ceil(total_assets as u128)).ok()
+    }
+
+    /// Assets redeemable for `shares` given the current vault balance and share
+    /// supply. Rounds down, in favour of the vault.
+    pub fn convert_to_assets(shares: u64, total_assets: u64, total_shares: u64) -> Option<u64> {
+        if total_shares == 0 {
+            return None;
+        }
+        u64::try_from((shares as u128) * (total_assets as u128) / (total_shares as u128)).ok()
+    }
+}
@@ -1,5 +1,7 @@
 mod amount;
 mod config;
+mod pending_withdrawal;
 
 pub use amount::*;
 pub use config::*;
+pub use pending_withdrawal::*;
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct PendingWithdrawal {
+    /// Shares taken out of the position; they stay in the share escrow until
+    /// claimed or cancelled.
+    pub shares: u64,
+    pub unlock_ts: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,337 @@
+//! Property test: random deposit/withdraw sequences across several users must
+//! always leave the sum of every `Amount` PDA equal to the share escrow
+//! balance, and to the vault ATA balance while shares stay 1:1 with assets.
+
+use anchor_lang::{prelude::msg, AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token;
+use litesvm::LiteSVM;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account, Mint},
+};
+
+use super::tests::{
+    build_init_tf_transaction, build_init_transaction, build_whitelist_transaction, get_config,
+    get_hook_account_metas, get_share_escrow_address, get_share_mint_address,
+    get_whitelist_address, setup, TestEnv, ASSOCIATED_TOKEN_PROGRAM, PROGRAM_ID,
+};
+
+const USERS: usize = 3;
+const STEPS: usize = 150;
+const MINTED_PER_USER: u64 = 1_000_000;
+
+/// Small deterministic xorshift generator, so failures are reproducible from the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+struct Vault {
+    mint: Pubkey,
+    token_program: Pubkey,
+    config: Pubkey,
+    vault: Pubkey,
+}
+
+impl Vault {
+    fn user_ata(&self, user: &Pubkey) -> Pubkey {
+        associated_token::get_associated_token_address_with_program_id(
+            user,
+            &self.mint,
+            &self.token_program,
+        )
+    }
+
+    fn amount_pda(&self, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"amount", self.config.as_ref(), user.as_ref()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn withdraw_accounts(&self, user: &Pubkey) -> Vec<AccountMeta> {
+        let whitelist = get_whitelist_address(self.mint, self.vault);
+        let mut accounts = crate::accounts::Withdraw {
+            user: *user,
+            amount_pda: self.amount_pda(user),
+            config: self.config,
+            mint: self.mint,
+            user_ata: self.user_ata(user),
+            vault: self.vault,
+            share_mint: get_share_mint_address(self.config),
+            share_escrow: get_share_escrow_address(self.config, self.token_program),
+            token_program: self.token_program,
+            associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+            system_program: SYSTEM_PROGRAM,
+        }
+        .to_account_metas(None);
+        accounts.extend(get_hook_account_metas(
+            self.mint,
+            whitelist,
+            get_whitelist_address(self.mint, self.user_ata(user)),
+        ));
+        accounts
+    }
+
+    fn mint_ix(&self, admin: &Pubkey, user: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::MintToken {
+                minter: *admin,
+                user: *user,
+                config: self.config,
+                mint: self.mint,
+                user_ata: self.user_ata(user),
+                token_program: self.token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(Some(true)),
+            data: crate::instruction::Mint { amount }.data(),
+        }
+    }
+
+    fn deposit_ix(&self, user: &Pubkey, amount: u64) -> Instruction {
+        let user_ata = self.user_ata(user);
+        let whitelist = get_whitelist_address(self.mint, user_ata);
+        let mut accounts = crate::accounts::Deposit {
+            user: *user,
+            amount_pda: self.amount_pda(user),
+            config: self.config,
+            mint: self.mint,
+            user_ata,
+            vault: self.vault,
+            share_mint: get_share_mint_address(self.config),
+            share_escrow: get_share_escrow_address(self.config, self.token_program),
+            token_program: self.token_program,
+            associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+            system_program: SYSTEM_PROGRAM,
+        }
+        .to_account_metas(Some(true));
+        accounts.extend(get_hook_account_metas(
+            self.mint,
+            whitelist,
+            get_whitelist_address(self.mint, self.vault),
+        ));
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::Deposit { amount }.data(),
+        }
+    }
+
+    fn withdraw_ix(&self, user: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: self.withdraw_accounts(user),
+            data: crate::instruction::Withdraw { amount }.data(),
+        }
+    }
+
+    fn withdraw_all_ix(&self, user: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: self.withdraw_accounts(user),
+            data: crate::instruction::WithdrawAll {}.data(),
+        }
+    }
+}
+
+fn send(
+    svm: &mut LiteSVM,
+    ix: Instruction,
+    payer: &Keypair,
+) -> Result<(), litesvm::types::FailedTransactionMetadata> {
+    let message = Message::new(&[ix], Some(&payer.pubkey()));
+    let transaction = Transaction::new(&[payer], message, svm.latest_blockhash());
+    let result = svm.send_transaction(transaction).map(|_| ());
+    // identical instructions are common in a random walk, never reuse a blockhash
+    svm.expire_blockhash();
+    result
+}
+
+fn token_balance(svm: &LiteSVM, address: &Pubkey) -> u64 {
+    let account = svm.get_account(address).unwrap();
+    StateWithExtensions::<Account>::unpack(&account.data)
+        .expect("Failed to deserialize token account data")
+        .base
+        .amount
+}
+
+fn share_supply(svm: &LiteSVM, share_mint: &Pubkey) -> u64 {
+    let account = svm.get_account(share_mint).unwrap();
+    StateWithExtensions::<Mint>::unpack(&account.data)
+        .expect("Failed to deserialize share mint data")
+        .base
+        .supply
+}
+
+fn deposited(svm: &LiteSVM, amount_pda: &Pubkey) -> u64 {
+    match svm.get_account(amount_pda) {
+        Some(account) => {
+            crate::Amount::try_deserialize(&mut account.data.as_slice())
+                .expect("Failed to deserialize Amount")
+                .shares
+        }
+        None => 0,
+    }
+}
+
+#[test]
+fn test_amounts_track_vault_balance() {
+    let TestEnv {
+        mut svm,
+        admin,
+        mint2022,
+        token_program,
+        config,
+        vault,
+        user_ata: _,
+    } = setup();
+
+    let recent_blockhash = svm.latest_blockhash();
+    svm.send_transaction(build_init_transaction(
+        &admin,
+        &mint2022,
+        token_program,
+        config,
+        vault,
+        recent_blockhash,
+    ))
+    .expect("Failed to send vault init tx");
+    svm.send_transaction(build_init_tf_transaction(
+        &admin,
+        &mint2022,
+        recent_blockhash,
+    ))
+    .expect("Failed to send init tf hook tx");
+    svm.send_transaction(build_whitelist_transaction(
+        &admin,
+        mint2022.pubkey(),
+        vault,
+        "add_to_whitelist",
+        recent_blockhash,
+    ))
+    .expect("Failed to whitelist the vault");
+    svm.expire_blockhash();
+
+    let env = Vault {
+        mint: mint2022.pubkey(),
+        token_program,
+        config,
+        vault,
+    };
+    let share_mint = get_share_mint_address(config);
+    let share_escrow = get_share_escrow_address(config, token_program);
+
+    let users: Vec<Keypair> = (0..USERS).map(|_| Keypair::new()).collect();
+    for user in &users {
+        svm.airdrop(&user.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to user.");
+        send(
+            &mut svm,
+            env.mint_ix(&admin.pubkey(), &user.pubkey(), MINTED_PER_USER),
+            &admin,
+        )
+        .expect("Failed to mint to user");
+        let transaction = build_whitelist_transaction(
+            &admin,
+            env.mint,
+            env.user_ata(&user.pubkey()),
+            "add_to_whitelist",
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to whitelist user ata");
+    }
+
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let mut expected = [0u64; USERS];
+
+    for step in 0..STEPS {
+        let index = rng.below(USERS as u64) as usize;
+        let user = &users[index];
+        let wallet = token_balance(&svm, &env.user_ata(&user.pubkey()));
+
+        match rng.below(4) {
+            0 | 1 if wallet > 0 => {
+                let amount = 1 + rng.below(wallet.min(50_000));
+                send(&mut svm, env.deposit_ix(&user.pubkey(), amount), user)
+                    .expect("Failed to deposit");
+                expected[index] += amount;
+            }
+            2 if expected[index] > 0 => {
+                let amount = 1 + rng.below(expected[index]);
+                send(&mut svm, env.withdraw_ix(&user.pubkey(), amount), user)
+                    .expect("Failed to withdraw");
+                expected[index] -= amount;
+            }
+            3 if expected[index] > 0 && rng.below(3) == 0 => {
+                send(&mut svm, env.withdraw_all_ix(&user.pubkey()), user)
+                    .expect("Failed to withdraw all");
+                expected[index] = 0;
+            }
+            _ if expected[index] > 0 => {
+                // over-withdrawal must fail cleanly and leave state untouched
+                let amount = expected[index] + 1 + rng.below(1_000);
+                let failed = send(&mut svm, env.withdraw_ix(&user.pubkey(), amount), user)
+                    .expect_err("Withdrawing more than deposited must fail");
+                let code: u32 = crate::error::VaultError::AmountExceededUrDeposit.into();
+                assert!(
+                    format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+                    "unexpected error: {:?}",
+                    failed.err
+                );
+            }
+            _ => continue,
+        }
+
+        let amounts: Vec<u64> = users
+            .iter()
+            .map(|user| deposited(&svm, &env.amount_pda(&user.pubkey())))
+            .collect();
+        let vault_balance = token_balance(&svm, &vault);
+        let accrued_fees = get_config(&svm, config).accrued_fees;
+
+        assert_eq!(amounts, expected, "step {}: Amount PDAs drifted", step);
+        assert_eq!(
+            amounts.iter().sum::<u64>() + accrued_fees,
+            vault_balance,
+            "step {}: sum of Amount PDAs != vault balance",
+            step
+        );
+        assert_eq!(
+            amounts.iter().sum::<u64>(),
+            token_balance(&svm, &share_escrow),
+            "step {}: sum of Amount PDAs != share escrow balance",
+            step
+        );
+        assert_eq!(
+            share_supply(&svm, &share_mint),
+            vault_balance - accrued_fees,
+            "step {}: shares no longer 1:1 with assets",
+            step
+        );
+    }
+
+    msg!("Invariant held over {} random steps", STEPS);
+}
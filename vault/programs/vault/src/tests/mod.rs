@@ -1,22 +1,38 @@
+#[cfg(test)]
+mod invariants;
+
 #[cfg(test)]
 mod tests {
 
     use anchor_lang::{
-        prelude::msg,
+        prelude::{msg, Clock},
         solana_program::hash::{hash, Hash},
-        InstructionData, ToAccountMetas,
+        AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator, InstructionData,
+        ToAccountMetas,
     };
     use anchor_spl::associated_token::{self, spl_associated_token_account};
+    use base64::{engine::general_purpose::STANDARD, Engine};
     use litesvm::LiteSVM;
+    use litesvm_token::{CreateAssociatedTokenAccount, CreateMint, MintTo};
+    use solana_account::Account as SolanaAccount;
     use solana_instruction::{AccountMeta, Instruction};
     use solana_keypair::Keypair;
     use solana_message::Message;
     use solana_native_token::LAMPORTS_PER_SOL;
     use solana_pubkey::Pubkey;
-    use solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM;
+    use solana_sdk_ids::{
+        bpf_loader_upgradeable::ID as BPF_LOADER_UPGRADEABLE, system_program::ID as SYSTEM_PROGRAM,
+    };
     use solana_signer::Signer;
     use solana_transaction::Transaction;
-    use spl_token_2022::{extension::StateWithExtensions, state::Account};
+    use spl_token_2022::{
+        extension::{
+            transfer_fee::{instruction::initialize_transfer_fee_config, TransferFeeConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        instruction::initialize_mint2,
+        state::{Account, Mint},
+    };
     use std::{fs::read, path::PathBuf, str::FromStr};
     pub struct TestEnv {
         pub svm: LiteSVM,
@@ -29,17 +45,56 @@ mod tests {
     }
 
     // TODO: ADD IT IN A SINGLE WORKSPACE, ALL THE PROGRAMS!
-    static PROGRAM_ID: Pubkey = crate::ID;
+    pub(super) static PROGRAM_ID: Pubkey = crate::ID;
 
-    const ASSOCIATED_TOKEN_PROGRAM: Pubkey = spl_associated_token_account::ID;
+    pub(super) const ASSOCIATED_TOKEN_PROGRAM: Pubkey = spl_associated_token_account::ID;
 
-    fn get_tf_hook_program_address() -> Pubkey {
+    pub(super) fn get_tf_hook_program_address() -> Pubkey {
         let transfer_hook_program =
             Pubkey::from_str("E6mxgYTtMfqneSJHxBZ9sP7VdJjW9FQsz1Dff8TsSN9p").unwrap();
         transfer_hook_program
     }
 
-    fn setup() -> TestEnv {
+    pub(super) fn get_share_mint_address(config: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"shares", config.as_ref()], &PROGRAM_ID).0
+    }
+
+    pub(super) fn get_user_shares_address(
+        user: Pubkey,
+        config: Pubkey,
+        token_program: Pubkey,
+    ) -> Pubkey {
+        associated_token::get_associated_token_address_with_program_id(
+            &user,
+            &get_share_mint_address(config),
+            &token_program,
+        )
+    }
+
+    pub(super) fn get_share_escrow_address(config: Pubkey, token_program: Pubkey) -> Pubkey {
+        associated_token::get_associated_token_address_with_program_id(
+            &config,
+            &get_share_mint_address(config),
+            &token_program,
+        )
+    }
+
+    /// Payloads of every event logged under `discriminator`, in log order. Events
+    /// are logged as base64 "Program data", discriminator first.
+    fn event_data(logs: &[String], discriminator: &[u8]) -> Vec<Vec<u8>> {
+        logs.iter()
+            .filter_map(|log| log.strip_prefix("Program data: "))
+            .filter_map(|data| STANDARD.decode(data).ok())
+            .filter_map(|bytes| bytes.strip_prefix(discriminator).map(<[u8]>::to_vec))
+            .collect()
+    }
+
+    /// Discriminator of a hook program event, which this crate has no types for.
+    fn hook_event_discriminator(name: &str) -> Vec<u8> {
+        hash(format!("event:{}", name).as_bytes()).to_bytes()[..8].to_vec()
+    }
+
+    pub(super) fn setup() -> TestEnv {
         let mut svm = LiteSVM::new();
         let admin = Keypair::new();
         let mint2022 = Keypair::new();
@@ -56,15 +111,26 @@ mod tests {
             read(whitelist_tf_hook_path).expect("Failed to read the program SO file!");
 
         svm.add_program(PROGRAM_ID, &vault_program_data);
-        svm.add_program(
+        add_upgradeable_program(
+            &mut svm,
             get_tf_hook_program_address(),
+            admin.pubkey(),
             &whitelist_tf_hook_program_data,
         );
 
         svm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL)
             .expect("Failed to airdrop SOL to admin.");
 
-        let config = Pubkey::find_program_address(&[b"config"], &PROGRAM_ID).0;
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_whitelist_admin_transaction(
+            &admin,
+            admin.pubkey(),
+            recent_blockhash,
+        ))
+        .expect("Failed to init the whitelist admin");
+
+        let config =
+            Pubkey::find_program_address(&[b"config", mint2022.pubkey().as_ref()], &PROGRAM_ID).0;
         let vault = associated_token::get_associated_token_address_with_program_id(
             &config,
             &mint2022.pubkey(),
@@ -87,12 +153,163 @@ mod tests {
         }
     }
 
-    fn build_init_transaction(
+    /// Deploys through the upgradeable loader so the hook has a ProgramData
+    /// account naming `upgrade_authority`.
+    fn add_upgradeable_program(
+        svm: &mut LiteSVM,
+        program_id: Pubkey,
+        upgrade_authority: Pubkey,
+        elf: &[u8],
+    ) {
+        let program_data =
+            Pubkey::find_program_address(&[program_id.as_ref()], &BPF_LOADER_UPGRADEABLE).0;
+
+        // UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address }
+        let mut data = 3u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(upgrade_authority.as_ref());
+        data.extend_from_slice(elf);
+        svm.set_account(
+            program_data,
+            SolanaAccount {
+                lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: BPF_LOADER_UPGRADEABLE,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .expect("Failed to set the program data account");
+
+        // UpgradeableLoaderState::Program { programdata_address }
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(program_data.as_ref());
+        svm.set_account(
+            program_id,
+            SolanaAccount {
+                lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: BPF_LOADER_UPGRADEABLE,
+                executable: true,
+                rent_epoch: 0,
+            },
+        )
+        .expect("Failed to set the program account");
+    }
+
+    pub(super) fn get_whitelist_admin_address() -> Pubkey {
+        Pubkey::find_program_address(&[b"whitelist-admin"], &get_tf_hook_program_address()).0
+    }
+
+    pub(super) fn build_init_whitelist_admin_transaction(
+        upgrade_authority: &Keypair,
+        admin: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let transfer_hook_program = get_tf_hook_program_address();
+        let program_data = Pubkey::find_program_address(
+            &[transfer_hook_program.as_ref()],
+            &BPF_LOADER_UPGRADEABLE,
+        )
+        .0;
+
+        let account_metas = vec![
+            AccountMeta::new(upgrade_authority.pubkey(), true),
+            AccountMeta::new(get_whitelist_admin_address(), false),
+            AccountMeta::new_readonly(transfer_hook_program, false),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+        ];
+
+        let mut data = hash(b"global:initialize_whitelist_admin").to_bytes()[..8].to_vec();
+        data.extend_from_slice(admin.as_ref());
+
+        let instruction = Instruction {
+            program_id: transfer_hook_program,
+            accounts: account_metas,
+            data,
+        };
+        let message = Message::new(&[instruction], Some(&upgrade_authority.pubkey()));
+
+        Transaction::new(&[upgrade_authority], message, recent_blockhash)
+    }
+
+    pub(super) fn build_set_whitelist_admin_transaction(
+        admin: &Keypair,
+        new_admin: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let account_metas = vec![
+            AccountMeta::new_readonly(admin.pubkey(), true),
+            AccountMeta::new(get_whitelist_admin_address(), false),
+        ];
+
+        let mut data = hash(b"global:set_whitelist_admin").to_bytes()[..8].to_vec();
+        data.extend_from_slice(new_admin.as_ref());
+
+        let instruction = Instruction {
+            program_id: get_tf_hook_program_address(),
+            accounts: account_metas,
+            data,
+        };
+        let message = Message::new(&[instruction], Some(&admin.pubkey()));
+
+        Transaction::new(&[admin], message, recent_blockhash)
+    }
+
+    fn build_set_hook_authority_transaction(
+        admin: &Keypair,
+        mint: Pubkey,
+        new_authority: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let account_metas = vec![
+            AccountMeta::new_readonly(admin.pubkey(), true),
+            AccountMeta::new_readonly(get_whitelist_admin_address(), false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(get_hook_config_address(mint), false),
+        ];
+
+        let mut data = hash(b"global:set_hook_authority").to_bytes()[..8].to_vec();
+        data.extend_from_slice(new_authority.as_ref());
+
+        let instruction = Instruction {
+            program_id: get_tf_hook_program_address(),
+            accounts: account_metas,
+            data,
+        };
+        let message = Message::new(&[instruction], Some(&admin.pubkey()));
+
+        Transaction::new(&[admin], message, recent_blockhash)
+    }
+
+    pub(super) fn build_init_transaction(
+        admin: &Keypair,
+        mint2022: &Keypair,
+        token_program: Pubkey,
+        config: Pubkey,
+        vault: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        build_init_transaction_with_decimals(
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            6,
+            recent_blockhash,
+        )
+    }
+
+    fn build_init_transaction_with_decimals(
         admin: &Keypair,
         mint2022: &Keypair,
         token_program: Pubkey,
         config: Pubkey,
         vault: Pubkey,
+        decimals: u8,
         recent_blockhash: Hash,
     ) -> Transaction {
         // this one is correct
@@ -103,13 +320,20 @@ mod tests {
                 config: config,
                 vault: vault,
                 mint: mint2022.pubkey(),
+                share_mint: get_share_mint_address(config),
                 transfer_hook_program: get_tf_hook_program_address(),
                 associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
                 system_program: SYSTEM_PROGRAM,
                 token_program,
             }
             .to_account_metas(Some(true)),
-            data: crate::instruction::InitializeVault {}.data(),
+            data: crate::instruction::InitializeVault {
+                decimals,
+                name: "Vault Token".to_string(),
+                symbol: "VLT".to_string(),
+                uri: "https://example.com/vault.json".to_string(),
+            }
+            .data(),
         };
 
         let message = Message::new(&[init_ix], Some(&admin.pubkey()));
@@ -121,6 +345,7 @@ mod tests {
         admin: &Keypair,
         mint2022: &Keypair,
         token_program: Pubkey,
+        config: Pubkey,
         user_ata: Pubkey,
         amount: u64,
         recent_blockhash: Hash,
@@ -128,8 +353,9 @@ mod tests {
         let mint_ix = Instruction {
             program_id: PROGRAM_ID,
             accounts: crate::accounts::MintToken {
-                admin: admin.pubkey(),
+                minter: admin.pubkey(),
                 user: admin.pubkey(),
+                config,
                 mint: mint2022.pubkey(),
                 user_ata,
                 associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
@@ -145,7 +371,7 @@ mod tests {
         Transaction::new(&[&admin], message, recent_blockhash)
     }
 
-    fn get_extra_account_metalist_pubkey(
+    pub(super) fn get_extra_account_metalist_pubkey(
         mint2022: &Keypair,
         transfer_hook_program: Pubkey,
     ) -> Pubkey {
@@ -156,7 +382,53 @@ mod tests {
         extra_account_meta_list
     }
 
-    fn build_init_tf_transaction(
+    pub(super) fn get_hook_config_address(mint: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"hook-config", mint.as_ref()],
+            &get_tf_hook_program_address(),
+        )
+        .0
+    }
+
+    pub(super) fn get_whitelist_address(mint: Pubkey, token_account: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"whitelist", mint.as_ref(), token_account.as_ref()],
+            &get_tf_hook_program_address(),
+        )
+        .0
+    }
+
+    pub(super) fn get_blocked_address(mint: Pubkey, account: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"blocked", mint.as_ref(), account.as_ref()],
+            &get_tf_hook_program_address(),
+        )
+        .0
+    }
+
+    /// The whitelist hook's accounts, passed to the vault as remaining accounts:
+    /// hook program, ExtraAccountMetaList, then the extra metas.
+    pub(super) fn get_hook_account_metas(
+        mint: Pubkey,
+        source_whitelist: Pubkey,
+        destination_whitelist: Pubkey,
+    ) -> Vec<AccountMeta> {
+        let transfer_hook_program = get_tf_hook_program_address();
+        let extra_account_meta_list = Pubkey::find_program_address(
+            &[b"extra-account-metas", mint.as_ref()],
+            &transfer_hook_program,
+        )
+        .0;
+        vec![
+            AccountMeta::new_readonly(transfer_hook_program, false),
+            AccountMeta::new_readonly(extra_account_meta_list, false),
+            AccountMeta::new_readonly(get_hook_config_address(mint), false),
+            AccountMeta::new_readonly(source_whitelist, false),
+            AccountMeta::new_readonly(destination_whitelist, false),
+        ]
+    }
+
+    pub(super) fn build_init_tf_transaction(
         admin: &Keypair,
         mint2022: &Keypair,
         recent_blockhash: Hash,
@@ -167,7 +439,9 @@ mod tests {
             get_extra_account_metalist_pubkey(&mint2022, transfer_hook_program);
         let account_metas = vec![
             AccountMeta::new(admin.pubkey(), true),
+            AccountMeta::new_readonly(get_whitelist_admin_address(), false),
             AccountMeta::new(extra_account_meta_list, false),
+            AccountMeta::new(get_hook_config_address(mint2022.pubkey()), false),
             AccountMeta::new(mint2022.pubkey(), false),
             AccountMeta::new(SYSTEM_PROGRAM, false),
         ];
@@ -189,23 +463,57 @@ mod tests {
         Transaction::new(&[&admin], message, recent_blockhash)
     }
 
-    fn build_whitelist_transaction(
+    pub(super) fn build_whitelist_transaction(
         admin: &Keypair,
+        mint: Pubkey,
         token_account: Pubkey,
         operation: &str,
         recent_blockhash: Hash,
     ) -> Transaction {
-        let transfer_hook_program = get_tf_hook_program_address();
+        build_list_transaction(
+            admin,
+            mint,
+            token_account,
+            get_whitelist_address(mint, token_account),
+            operation,
+            recent_blockhash,
+        )
+    }
 
-        let whitelist = Pubkey::find_program_address(
-            &[b"whitelist", token_account.as_ref()],
-            &transfer_hook_program,
+    fn build_blocklist_transaction(
+        admin: &Keypair,
+        mint: Pubkey,
+        account: Pubkey,
+        operation: &str,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        build_list_transaction(
+            admin,
+            mint,
+            account,
+            get_blocked_address(mint, account),
+            operation,
+            recent_blockhash,
         )
-        .0;
+    }
+
+    /// Shared by the whitelist and blocklist operations, which take the same
+    /// accounts with `entry` as the list PDA.
+    fn build_list_transaction(
+        admin: &Keypair,
+        mint: Pubkey,
+        token_account: Pubkey,
+        entry: Pubkey,
+        operation: &str,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let transfer_hook_program = get_tf_hook_program_address();
 
         let account_metas = vec![
             AccountMeta::new(admin.pubkey(), true),
-            AccountMeta::new(whitelist, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(get_hook_config_address(mint), false),
+            AccountMeta::new(entry, false),
             AccountMeta::new(SYSTEM_PROGRAM, false),
         ];
 
@@ -227,6 +535,41 @@ mod tests {
         Transaction::new(&[&admin], message, recent_blockhash)
     }
 
+    fn build_hook_config_transaction(
+        admin: &Keypair,
+        mint: Pubkey,
+        operation: &str,
+        value: u8,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let transfer_hook_program = get_tf_hook_program_address();
+        let extra_account_meta_list = Pubkey::find_program_address(
+            &[b"extra-account-metas", mint.as_ref()],
+            &transfer_hook_program,
+        )
+        .0;
+
+        let account_metas = vec![
+            AccountMeta::new_readonly(admin.pubkey(), true),
+            AccountMeta::new(get_hook_config_address(mint), false),
+            AccountMeta::new(extra_account_meta_list, false),
+            AccountMeta::new_readonly(mint, false),
+        ];
+
+        let string = format!("global:{}", operation);
+        let mut data = hash(string.as_bytes()).to_bytes()[..8].to_vec();
+        data.push(value);
+
+        let instruction = Instruction {
+            program_id: transfer_hook_program,
+            accounts: account_metas,
+            data,
+        };
+        let message = Message::new(&[instruction], Some(&admin.pubkey()));
+
+        Transaction::new(&[admin], message, recent_blockhash)
+    }
+
     fn build_deposit_transaction(
         admin: &Keypair,
         mint2022: &Keypair,
@@ -236,34 +579,101 @@ mod tests {
         user_ata: Pubkey,
         recent_blockhash: Hash,
     ) -> Transaction {
-        let amount_pda =
-            Pubkey::find_program_address(&[b"amount", admin.pubkey().as_ref()], &PROGRAM_ID).0;
-        let transfer_hook_program = get_tf_hook_program_address();
-        let extra_account_meta_list =
-            get_extra_account_metalist_pubkey(&mint2022, transfer_hook_program);
-        let user_whitelist = Pubkey::find_program_address(
-            &[b"whitelist", user_ata.as_ref()],
-            &transfer_hook_program,
+        build_deposit_transaction_with_data(
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            crate::instruction::Deposit { amount: 100 }.data(),
+            recent_blockhash,
         )
-        .0;
+    }
+
+    fn build_deposit_transaction_with_data(
+        admin: &Keypair,
+        mint2022: &Keypair,
+        token_program: Pubkey,
+        config: Pubkey,
+        vault: Pubkey,
+        user_ata: Pubkey,
+        data: Vec<u8>,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let accounts =
+            get_deposit_account_metas(admin, mint2022, token_program, config, vault, user_ata);
         // this one is correct
         let deposit_ix = Instruction {
             program_id: PROGRAM_ID,
-            accounts: crate::accounts::Deposit {
-                user: admin.pubkey(),
-                user_ata,
-                config: config,
-                amount_pda,
-                vault: vault,
-                mint: mint2022.pubkey(),
-                transfer_hook_program,
-                whitelist: user_whitelist,
-                extra_account_meta_list,
-                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
-                token_program,
-                system_program: SYSTEM_PROGRAM,
-            }
-            .to_account_metas(Some(true)),
+            accounts,
+            data,
+        };
+
+        let message = Message::new(&[deposit_ix], Some(&admin.pubkey()));
+
+        Transaction::new(&[&admin], message, recent_blockhash)
+    }
+
+    /// Deposit accounts followed by the transfer-hook remaining accounts.
+    fn get_deposit_account_metas(
+        admin: &Keypair,
+        mint2022: &Keypair,
+        token_program: Pubkey,
+        config: Pubkey,
+        vault: Pubkey,
+        user_ata: Pubkey,
+    ) -> Vec<AccountMeta> {
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        let user_whitelist = get_whitelist_address(mint2022.pubkey(), user_ata);
+        let mut accounts = crate::accounts::Deposit {
+            user: admin.pubkey(),
+            user_ata,
+            config: config,
+            amount_pda,
+            vault: vault,
+            mint: mint2022.pubkey(),
+            share_mint: get_share_mint_address(config),
+            share_escrow: get_share_escrow_address(config, token_program),
+            associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+            token_program,
+            system_program: SYSTEM_PROGRAM,
+        }
+        .to_account_metas(Some(true));
+        accounts.extend(get_hook_account_metas(
+            mint2022.pubkey(),
+            user_whitelist,
+            get_whitelist_address(mint2022.pubkey(), vault),
+        ));
+        accounts
+    }
+
+    /// Deposit with the hook's list entries derived from owners, for mints in owner mode.
+    fn build_owner_mode_deposit_transaction(
+        admin: &Keypair,
+        mint2022: &Keypair,
+        token_program: Pubkey,
+        config: Pubkey,
+        vault: Pubkey,
+        user_ata: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let mut accounts =
+            get_deposit_account_metas(admin, mint2022, token_program, config, vault, user_ata);
+        let entries = accounts.len() - 2;
+        accounts[entries] = AccountMeta::new_readonly(
+            get_whitelist_address(mint2022.pubkey(), admin.pubkey()),
+            false,
+        );
+        accounts[entries + 1] =
+            AccountMeta::new_readonly(get_whitelist_address(mint2022.pubkey(), config), false);
+        let deposit_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
             data: crate::instruction::Deposit { amount: 100 }.data(),
         };
 
@@ -272,6 +682,34 @@ mod tests {
         Transaction::new(&[&admin], message, recent_blockhash)
     }
 
+    fn get_withdraw_accounts(
+        admin: &Keypair,
+        mint2022: &Keypair,
+        token_program: Pubkey,
+        config: Pubkey,
+        vault: Pubkey,
+        user_ata: Pubkey,
+    ) -> crate::accounts::Withdraw {
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        crate::accounts::Withdraw {
+            user: admin.pubkey(),
+            user_ata,
+            config: config,
+            amount_pda,
+            vault: vault,
+            mint: mint2022.pubkey(),
+            share_mint: get_share_mint_address(config),
+            share_escrow: get_share_escrow_address(config, token_program),
+            associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+            token_program,
+            system_program: SYSTEM_PROGRAM,
+        }
+    }
+
     fn build_withdraw_transaction(
         admin: &Keypair,
         mint2022: &Keypair,
@@ -282,28 +720,17 @@ mod tests {
         whitelist: Pubkey,
         recent_blockhash: Hash,
     ) -> Transaction {
-        let amount_pda =
-            Pubkey::find_program_address(&[b"amount", admin.pubkey().as_ref()], &PROGRAM_ID).0;
-        let transfer_hook_program = get_tf_hook_program_address();
-        let extra_account_meta_list =
-            get_extra_account_metalist_pubkey(&mint2022, transfer_hook_program);
+        let mut accounts =
+            get_withdraw_accounts(admin, mint2022, token_program, config, vault, user_ata)
+                .to_account_metas(None);
+        accounts.extend(get_hook_account_metas(
+            mint2022.pubkey(),
+            whitelist,
+            get_whitelist_address(mint2022.pubkey(), user_ata),
+        ));
         let withdraw_ix = Instruction {
             program_id: PROGRAM_ID,
-            accounts: crate::accounts::Withdraw {
-                user: admin.pubkey(),
-                user_ata,
-                config: config,
-                amount_pda,
-                vault: vault,
-                mint: mint2022.pubkey(),
-                transfer_hook_program,
-                whitelist,
-                extra_account_meta_list,
-                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
-                token_program,
-                system_program: SYSTEM_PROGRAM,
-            }
-            .to_account_metas(None),
+            accounts,
             data: crate::instruction::Withdraw { amount: 100 }.data(),
         };
 
@@ -312,7 +739,531 @@ mod tests {
         Transaction::new(&[&admin], message, recent_blockhash)
     }
 
-    #[test]
+    fn get_pending_withdrawal_address(admin: &Keypair, config: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"withdrawal", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn build_request_withdraw_transaction(
+        admin: &Keypair,
+        config: Pubkey,
+        shares: u64,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        let request_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::RequestWithdraw {
+                user: admin.pubkey(),
+                config,
+                amount_pda,
+                pending_withdrawal: get_pending_withdrawal_address(admin, config),
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::RequestWithdraw { shares }.data(),
+        };
+
+        let message = Message::new(&[request_ix], Some(&admin.pubkey()));
+
+        Transaction::new(&[&admin], message, recent_blockhash)
+    }
+
+    fn build_cancel_withdraw_transaction(
+        admin: &Keypair,
+        config: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let cancel_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::CancelWithdraw {
+                user: admin.pubkey(),
+                config,
+                amount_pda: get_amount_address(admin.pubkey(), config),
+                pending_withdrawal: get_pending_withdrawal_address(admin, config),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::CancelWithdraw {}.data(),
+        };
+
+        let message = Message::new(&[cancel_ix], Some(&admin.pubkey()));
+
+        Transaction::new(&[&admin], message, recent_blockhash)
+    }
+
+    fn build_claim_withdraw_transaction(
+        admin: &Keypair,
+        mint2022: &Keypair,
+        token_program: Pubkey,
+        config: Pubkey,
+        vault: Pubkey,
+        user_ata: Pubkey,
+        whitelist: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let mut accounts = crate::accounts::ClaimWithdraw {
+            withdraw: get_withdraw_accounts(
+                admin,
+                mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+            ),
+            pending_withdrawal: get_pending_withdrawal_address(admin, config),
+        }
+        .to_account_metas(None);
+        accounts.extend(get_hook_account_metas(
+            mint2022.pubkey(),
+            whitelist,
+            get_whitelist_address(mint2022.pubkey(), user_ata),
+        ));
+        let claim_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::ClaimWithdraw {}.data(),
+        };
+
+        let message = Message::new(&[claim_ix], Some(&admin.pubkey()));
+
+        Transaction::new(&[&admin], message, recent_blockhash)
+    }
+
+    fn build_close_position_transaction(
+        admin: &Keypair,
+        config: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        let close_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ClosePosition {
+                user: admin.pubkey(),
+                amount_pda,
+                config,
+                pending_withdrawal: get_pending_withdrawal_address(admin, config),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::ClosePosition {}.data(),
+        };
+
+        let message = Message::new(&[close_ix], Some(&admin.pubkey()));
+
+        Transaction::new(&[&admin], message, recent_blockhash)
+    }
+
+    fn build_admin_transaction(
+        signer: &Keypair,
+        config: Pubkey,
+        data: Vec<u8>,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let admin_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::AdminOperations {
+                admin: signer.pubkey(),
+                config,
+            }
+            .to_account_metas(None),
+            data,
+        };
+
+        let message = Message::new(&[admin_ix], Some(&signer.pubkey()));
+
+        Transaction::new(&[&signer], message, recent_blockhash)
+    }
+
+    fn build_accept_admin_transaction(
+        new_admin: &Keypair,
+        config: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let accept_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::AcceptAdmin {
+                new_admin: new_admin.pubkey(),
+                config,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::AcceptAdmin {}.data(),
+        };
+
+        let message = Message::new(&[accept_ix], Some(&new_admin.pubkey()));
+
+        Transaction::new(&[&new_admin], message, recent_blockhash)
+    }
+
+    pub(super) fn get_config(svm: &LiteSVM, config: Pubkey) -> crate::Config {
+        let account = svm.get_account(&config).unwrap();
+        crate::Config::try_deserialize(&mut account.data.as_slice())
+            .expect("Failed to deserialize config")
+    }
+
+    fn build_set_pause_transaction(
+        authority: &Keypair,
+        config: Pubkey,
+        paused: u8,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let pause_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::SetPause {
+                authority: authority.pubkey(),
+                config,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::SetPause { paused }.data(),
+        };
+
+        let message = Message::new(&[pause_ix], Some(&authority.pubkey()));
+
+        Transaction::new(&[&authority], message, recent_blockhash)
+    }
+
+    fn build_collect_fees_transaction(
+        admin: &Keypair,
+        mint2022: &Keypair,
+        token_program: Pubkey,
+        config: Pubkey,
+        vault: Pubkey,
+        treasury: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let vault_whitelist = get_whitelist_address(mint2022.pubkey(), vault);
+        let mut accounts = crate::accounts::CollectFees {
+            authority: admin.pubkey(),
+            config,
+            mint: mint2022.pubkey(),
+            vault,
+            treasury,
+            token_program,
+        }
+        .to_account_metas(None);
+        accounts.extend(get_hook_account_metas(
+            mint2022.pubkey(),
+            vault_whitelist,
+            get_whitelist_address(mint2022.pubkey(), treasury),
+        ));
+        let collect_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::CollectFees {}.data(),
+        };
+
+        let message = Message::new(&[collect_ix], Some(&admin.pubkey()));
+
+        Transaction::new(&[&admin], message, recent_blockhash)
+    }
+
+    fn get_amount_address(user: Pubkey, config: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"amount", config.as_ref(), user.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn get_position(svm: &LiteSVM, user: Pubkey, config: Pubkey) -> crate::Amount {
+        let account = svm.get_account(&get_amount_address(user, config)).unwrap();
+        crate::Amount::try_deserialize(&mut account.data.as_slice())
+            .expect("Failed to deserialize position")
+    }
+
+    fn build_stake_transaction(
+        user: &Keypair,
+        config: Pubkey,
+        vault: Pubkey,
+        token_program: Pubkey,
+        shares: u64,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let stake_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Stake {
+                user: user.pubkey(),
+                config,
+                amount_pda: get_amount_address(user.pubkey(), config),
+                share_mint: get_share_mint_address(config),
+                vault,
+                user_shares: get_user_shares_address(user.pubkey(), config, token_program),
+                share_escrow: get_share_escrow_address(config, token_program),
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Stake { shares }.data(),
+        };
+
+        let message = Message::new(&[stake_ix], Some(&user.pubkey()));
+
+        Transaction::new(&[&user], message, recent_blockhash)
+    }
+
+    fn build_expire_lock_transaction(
+        caller: &Keypair,
+        owner: Pubkey,
+        config: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let expire_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ExpireLock {
+                config,
+                owner,
+                amount_pda: get_amount_address(owner, config),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::ExpireLock {}.data(),
+        };
+
+        let message = Message::new(&[expire_ix], Some(&caller.pubkey()));
+
+        Transaction::new(&[&caller], message, recent_blockhash)
+    }
+
+    fn build_unstake_transaction(
+        user: &Keypair,
+        config: Pubkey,
+        token_program: Pubkey,
+        shares: u64,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let unstake_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Unstake {
+                user: user.pubkey(),
+                config,
+                amount_pda: get_amount_address(user.pubkey(), config),
+                share_mint: get_share_mint_address(config),
+                user_shares: get_user_shares_address(user.pubkey(), config, token_program),
+                share_escrow: get_share_escrow_address(config, token_program),
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Unstake { shares }.data(),
+        };
+
+        let message = Message::new(&[unstake_ix], Some(&user.pubkey()));
+
+        Transaction::new(&[&user], message, recent_blockhash)
+    }
+
+    /// Sends `shares` from `from`'s share wallet to `to`'s, creating it first.
+    fn build_share_transfer_transaction(
+        from: &Keypair,
+        to: Pubkey,
+        config: Pubkey,
+        token_program: Pubkey,
+        shares: u64,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let share_mint = get_share_mint_address(config);
+        let create_ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &from.pubkey(),
+            &to,
+            &share_mint,
+            &token_program,
+        );
+        let transfer_ix = spl_token_2022::instruction::transfer_checked(
+            &token_program,
+            &get_user_shares_address(from.pubkey(), config, token_program),
+            &share_mint,
+            &get_user_shares_address(to, config, token_program),
+            &from.pubkey(),
+            &[],
+            shares,
+            6,
+        )
+        .unwrap();
+
+        let message = Message::new(&[create_ix, transfer_ix], Some(&from.pubkey()));
+
+        Transaction::new(&[&from], message, recent_blockhash)
+    }
+
+    fn build_configure_rewards_transaction(
+        admin: &Keypair,
+        config: Pubkey,
+        reward_mint: Pubkey,
+        reward_token_program: Pubkey,
+        reward_rate: u64,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let configure_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ConfigureRewards {
+                admin: admin.pubkey(),
+                config,
+                reward_mint,
+                reward_vault: associated_token::get_associated_token_address_with_program_id(
+                    &config,
+                    &reward_mint,
+                    &reward_token_program,
+                ),
+                reward_token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::ConfigureRewards { reward_rate }.data(),
+        };
+
+        let message = Message::new(&[configure_ix], Some(&admin.pubkey()));
+
+        Transaction::new(&[&admin], message, recent_blockhash)
+    }
+
+    fn build_claim_rewards_transaction(
+        user: &Keypair,
+        config: Pubkey,
+        reward_mint: Pubkey,
+        reward_token_program: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let claim_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ClaimRewards {
+                user: user.pubkey(),
+                config,
+                amount_pda: get_amount_address(user.pubkey(), config),
+                reward_mint,
+                reward_vault: associated_token::get_associated_token_address_with_program_id(
+                    &config,
+                    &reward_mint,
+                    &reward_token_program,
+                ),
+                user_reward_ata: associated_token::get_associated_token_address_with_program_id(
+                    &user.pubkey(),
+                    &reward_mint,
+                    &reward_token_program,
+                ),
+                reward_token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::ClaimRewards {}.data(),
+        };
+
+        let message = Message::new(&[claim_ix], Some(&user.pubkey()));
+
+        Transaction::new(&[&user], message, recent_blockhash)
+    }
+
+    /// Plain SPL reward mint, funded and configured at `reward_rate` per second.
+    fn setup_rewards(
+        svm: &mut LiteSVM,
+        admin: &Keypair,
+        config: Pubkey,
+        reward_rate: u64,
+    ) -> Pubkey {
+        let reward_token_program = anchor_spl::token::ID;
+        let reward_mint = CreateMint::new(svm, admin)
+            .authority(&admin.pubkey())
+            .decimals(6)
+            .send()
+            .expect("Failed to create reward mint");
+        let transaction = build_configure_rewards_transaction(
+            admin,
+            config,
+            reward_mint,
+            reward_token_program,
+            reward_rate,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send configure rewards txn");
+        let reward_vault = associated_token::get_associated_token_address_with_program_id(
+            &config,
+            &reward_mint,
+            &reward_token_program,
+        );
+        MintTo::new(svm, admin, &reward_mint, &reward_vault, 1_000_000)
+            .send()
+            .expect("Failed to fund the reward vault");
+        reward_mint
+    }
+
+    fn reward_balance(svm: &LiteSVM, user: Pubkey, reward_mint: Pubkey) -> u64 {
+        let reward_ata = associated_token::get_associated_token_address_with_program_id(
+            &user,
+            &reward_mint,
+            &anchor_spl::token::ID,
+        );
+        let account = svm.get_account(&reward_ata).unwrap();
+        StateWithExtensions::<Account>::unpack(&account.data)
+            .expect("Failed to deserialize reward account data")
+            .base
+            .amount
+    }
+
+    /// Initializes the vault and hook, whitelists `user_ata` and the vault and
+    /// deposits 100 from `user_ata`.
+    fn setup_deposit(
+        svm: &mut LiteSVM,
+        admin: &Keypair,
+        mint2022: &Keypair,
+        token_program: Pubkey,
+        config: Pubkey,
+        vault: Pubkey,
+        user_ata: Pubkey,
+    ) {
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(admin, mint2022, recent_blockhash))
+            .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            admin,
+            mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        for account in [user_ata, vault] {
+            svm.send_transaction(build_whitelist_transaction(
+                admin,
+                mint2022.pubkey(),
+                account,
+                "add_to_whitelist",
+                recent_blockhash,
+            ))
+            .expect("Failed to send whitelist txn");
+        }
+        svm.send_transaction(build_deposit_transaction(
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            recent_blockhash,
+        ))
+        .expect("Failed to send Deposit txn");
+        svm.expire_blockhash();
+    }
+
+    #[test]
     fn test_init_vault() {
         let TestEnv {
             mut svm,
@@ -321,28 +1272,3898 @@ mod tests {
             token_program,
             config,
             vault,
-            user_ata: _,
+            user_ata: _,
+        } = setup();
+        let recent_blockhash = svm.latest_blockhash();
+        let transaction = build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        );
+
+        let tx = svm.send_transaction(transaction).unwrap();
+        let config_state = get_config(&svm, config);
+        assert_eq!(config_state.decimals, 6);
+        assert_eq!(
+            config_state.transfer_hook_program,
+            get_tf_hook_program_address()
+        );
+
+        // Log transaction details
+        msg!("\n\nInit transaction sucessfull");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_mint() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        let transaction1 = build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        );
+        let _tx1 = svm.send_transaction(transaction1).unwrap();
+
+        let amount = 1_000_000;
+
+        let transaction2 = build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            amount,
+            recent_blockhash,
+        );
+        let tx2 = svm
+            .send_transaction(transaction2)
+            .expect("Failed to send mint token transaction!");
+
+        // Log transaction details
+        msg!("\n\n Mint transaction sucessfull");
+        msg!("CUs Consumed: {}", tx2.compute_units_consumed);
+        msg!("Tx Signature: {}", tx2.signature);
+
+        let token_account = svm.get_account(&user_ata).unwrap();
+        let token_state = StateWithExtensions::<Account>::unpack(&token_account.data)
+            .expect("Failed to deserialize token account data");
+        msg!("token state: {:?}", token_state.base);
+        assert_eq!(token_state.base.amount, amount);
+    }
+
+    #[test]
+    fn test_initialize_transfer_hook() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata: _,
+        } = setup();
+        let recent_blockhash = svm.latest_blockhash();
+        let transaction1 = build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        );
+        let _tx1 = svm
+            .send_transaction(transaction1)
+            .expect("Failed to send init vault tx");
+
+        let transaction2 = build_init_tf_transaction(&admin, &mint2022, recent_blockhash);
+
+        let tx2 = svm
+            .send_transaction(transaction2)
+            .expect("Failed to send init tf hoook tx");
+
+        // Log transaction details
+        msg!("\n\n Init tf hook transaction sucessfull");
+        msg!("CUs Consumed: {}", tx2.compute_units_consumed);
+        msg!("Tx Signature: {}", tx2.signature);
+    }
+
+    #[test]
+    fn test_add_to_whitelist() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata: _,
+        } = setup();
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        // whitelist entries live under the mint's hook config
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        let transaction = build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            vault,
+            "add_to_whitelist",
+            recent_blockhash,
+        );
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to send init tf hoook tx");
+
+        // WhitelistAdded { mint, address, mode }
+        let added = event_data(&tx.logs, &hook_event_discriminator("WhitelistAdded"));
+        assert_eq!(added.len(), 1);
+        assert_eq!(&added[0][0..32], mint2022.pubkey().as_ref());
+        assert_eq!(&added[0][32..64], vault.as_ref());
+        assert_eq!(added[0][64], 0);
+
+        // Log transaction details
+        msg!("\n\n Add to whitelist transaction sucessfull");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_remove_from_whitelist() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata: _,
+        } = setup();
+        let recent_blockhash = svm.latest_blockhash();
+
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+
+        let transaction1 = build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            vault,
+            "add_to_whitelist",
+            recent_blockhash,
+        );
+        let _tx1 = svm
+            .send_transaction(transaction1)
+            .expect("Failed to send init tf hoook tx");
+
+        let transaction = build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            vault,
+            "remove_from_whitelist",
+            recent_blockhash,
+        );
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to send remove from whitelist tx");
+
+        // Log transaction details
+        msg!("\n\n Remove from whitelist transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_deposit() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        let transaction1 = build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        );
+        let _tx1 = svm
+            .send_transaction(transaction1)
+            .expect("Failed to send vault init tx");
+
+        let transaction2 = build_init_tf_transaction(&admin, &mint2022, recent_blockhash);
+        let _tx2 = svm
+            .send_transaction(transaction2)
+            .expect("Failed to send init tf hoook tx");
+
+        let amount = 1_000_000;
+        let transaction3 = build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            amount,
+            recent_blockhash,
+        );
+        let _tx3 = svm
+            .send_transaction(transaction3)
+            .expect("Failed to send mint txn");
+
+        let token_account = svm.get_account(&user_ata).unwrap();
+        let token_state = StateWithExtensions::<Account>::unpack(&token_account.data)
+            .expect("Failed to deserialize token account data");
+        msg!("token state: {:?}", token_state.base);
+
+        let transaction4 = build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            user_ata,
+            "add_to_whitelist",
+            recent_blockhash,
+        );
+        let _tx4 = svm
+            .send_transaction(transaction4)
+            .expect("Failed to send whitelist txn");
+
+        let transaction = build_deposit_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            recent_blockhash,
+        );
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to send Deposit txn");
+
+        let share_escrow = get_share_escrow_address(config, token_program);
+        let shares_account = svm.get_account(&share_escrow).unwrap();
+        let shares_state = StateWithExtensions::<Account>::unpack(&shares_account.data)
+            .expect("Failed to deserialize share account data");
+        assert_eq!(shares_state.base.amount, 100);
+
+        let events = event_data(&tx.logs, crate::Deposited::DISCRIMINATOR);
+        assert_eq!(events.len(), 1);
+        let deposited = crate::Deposited::deserialize(&mut events[0].as_slice())
+            .expect("Failed to deserialize Deposited");
+        assert_eq!(deposited.config, config);
+        assert_eq!(deposited.user, admin.pubkey());
+        assert_eq!(deposited.received, 100);
+        assert_eq!(deposited.fee, 0);
+        assert_eq!(deposited.shares, 100);
+
+        // the hook approves the transfer into the vault in the same transaction:
+        // mint, source, destination, owner, then the amount
+        let approved = event_data(&tx.logs, &hook_event_discriminator("TransferApproved"));
+        assert_eq!(approved.len(), 1);
+        assert_eq!(&approved[0][0..32], mint2022.pubkey().as_ref());
+        assert_eq!(&approved[0][32..64], user_ata.as_ref());
+        assert_eq!(&approved[0][64..96], vault.as_ref());
+        assert_eq!(&approved[0][96..128], admin.pubkey().as_ref());
+        assert_eq!(approved[0][128..136], 100u64.to_le_bytes());
+
+        // Log transaction details
+        msg!("\n\n Desposit transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        let vault_whitelist = get_whitelist_address(mint2022.pubkey(), vault);
+        let transaction1 = build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        );
+        let _tx1 = svm
+            .send_transaction(transaction1)
+            .expect("Failed to send vault init tx");
+
+        let transaction2 = build_init_tf_transaction(&admin, &mint2022, recent_blockhash);
+        let _tx2 = svm
+            .send_transaction(transaction2)
+            .expect("Failed to send init tf hoook tx");
+
+        let amount = 1_000_000;
+        let transaction3 = build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            amount,
+            recent_blockhash,
+        );
+        let _tx3 = svm
+            .send_transaction(transaction3)
+            .expect("Failed to send mint txn");
+
+        let transaction4 = build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            user_ata,
+            "add_to_whitelist",
+            recent_blockhash,
+        );
+        let _tx4 = svm
+            .send_transaction(transaction4)
+            .expect("Failed to send whitelist it txn");
+
+        let transaction5 = build_deposit_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            recent_blockhash,
+        );
+        let _tx5 = svm
+            .send_transaction(transaction5)
+            .expect("Failed to send Deposit txn");
+
+        let transaction6 = build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            vault,
+            "add_to_whitelist",
+            recent_blockhash,
+        );
+        let _tx6 = svm
+            .send_transaction(transaction6)
+            .expect("Failed to send whitelist it txn");
+
+        let transaction = build_withdraw_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            vault_whitelist,
+            recent_blockhash,
+        );
+
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to send withdraw txn");
+
+        let share_escrow = get_share_escrow_address(config, token_program);
+        let shares_account = svm.get_account(&share_escrow).unwrap();
+        let shares_state = StateWithExtensions::<Account>::unpack(&shares_account.data)
+            .expect("Failed to deserialize share account data");
+        assert_eq!(shares_state.base.amount, 0);
+        // Log transaction details
+        msg!("\n\n Withdraw transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_close_position() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        let vault_whitelist = get_whitelist_address(mint2022.pubkey(), vault);
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            user_ata,
+            "add_to_whitelist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send whitelist txn");
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            vault,
+            "add_to_whitelist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send whitelist txn");
+        svm.send_transaction(build_deposit_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            recent_blockhash,
+        ))
+        .expect("Failed to send Deposit txn");
+
+        // the position still holds a balance, so it must stay open
+        let failed = svm
+            .send_transaction(build_close_position_transaction(
+                &admin,
+                config,
+                recent_blockhash,
+            ))
+            .expect_err("Closing a non-empty position should fail");
+        let code: u32 = crate::error::VaultError::PositionNotEmpty.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        svm.send_transaction(build_withdraw_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            vault_whitelist,
+            recent_blockhash,
+        ))
+        .expect("Failed to send withdraw txn");
+
+        svm.expire_blockhash();
+        let tx = svm
+            .send_transaction(build_close_position_transaction(
+                &admin,
+                config,
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send close position txn");
+        assert!(svm.get_account(&amount_pda).is_none());
+
+        // Log transaction details
+        msg!("\n\n Close position transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_admin_handover() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata: _,
+        } = setup();
+
+        let new_admin = Keypair::new();
+        let pauser = Keypair::new();
+        svm.airdrop(&new_admin.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to new admin.");
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+
+        // nobody can accept before a proposal exists
+        let failed = svm
+            .send_transaction(build_accept_admin_transaction(
+                &new_admin,
+                config,
+                recent_blockhash,
+            ))
+            .expect_err("Accepting without a proposal should fail");
+        let code: u32 = crate::error::VaultError::NotPendingAdmin.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::ProposeAdmin {
+                new_admin: new_admin.pubkey(),
+            }
+            .data(),
+            recent_blockhash,
+        ))
+        .expect("Failed to send propose admin txn");
+        assert_eq!(get_config(&svm, config).admin, admin.pubkey());
+
+        svm.expire_blockhash();
+        let tx = svm
+            .send_transaction(build_accept_admin_transaction(
+                &new_admin,
+                config,
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send accept admin txn");
+
+        let state = get_config(&svm, config);
+        assert_eq!(state.admin, new_admin.pubkey());
+        assert_eq!(state.pending_admin, None);
+
+        // the old key lost its powers, the new one can rotate roles
+        let failed = svm
+            .send_transaction(build_admin_transaction(
+                &admin,
+                config,
+                crate::instruction::SetRole {
+                    role: crate::Role::Pauser,
+                    account: pauser.pubkey(),
+                }
+                .data(),
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Old admin should not be able to set roles");
+        let code: u32 = crate::error::VaultError::NotAdmin.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+        svm.send_transaction(build_admin_transaction(
+            &new_admin,
+            config,
+            crate::instruction::SetRole {
+                role: crate::Role::Pauser,
+                account: pauser.pubkey(),
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set role txn");
+        assert_eq!(get_config(&svm, config).pauser, pauser.pubkey());
+
+        // Log transaction details
+        msg!("\n\n Admin handover transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_pause_mint() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+
+        let stranger = Keypair::new();
+        svm.airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to stranger.");
+        let failed = svm
+            .send_transaction(build_set_pause_transaction(
+                &stranger,
+                config,
+                crate::PAUSE_MINT,
+                recent_blockhash,
+            ))
+            .expect_err("Only the admin or pauser can pause");
+        let code: u32 = crate::error::VaultError::NotPauser.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        let tx = svm
+            .send_transaction(build_set_pause_transaction(
+                &admin,
+                config,
+                crate::PAUSE_MINT,
+                recent_blockhash,
+            ))
+            .expect("Failed to send set pause txn");
+
+        let failed = svm
+            .send_transaction(build_mint_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                user_ata,
+                1_000_000,
+                recent_blockhash,
+            ))
+            .expect_err("Minting while paused should fail");
+        let code: u32 = crate::error::VaultError::Paused.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        svm.send_transaction(build_set_pause_transaction(
+            &admin,
+            config,
+            0,
+            recent_blockhash,
+        ))
+        .expect("Failed to send unpause txn");
+
+        svm.expire_blockhash();
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send mint txn after unpausing");
+
+        // Log transaction details
+        msg!("\n\n Pause transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_deposit_caps() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            user_ata,
+            "add_to_whitelist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send whitelist txn");
+
+        // the deposit helper moves 100 tokens, one more than either cap allows
+        for (max_total_deposits, max_per_user, error) in [
+            (
+                u64::MAX,
+                99,
+                crate::error::VaultError::UserDepositCapExceeded,
+            ),
+            (99, u64::MAX, crate::error::VaultError::DepositCapExceeded),
+        ] {
+            svm.expire_blockhash();
+            svm.send_transaction(build_admin_transaction(
+                &admin,
+                config,
+                crate::instruction::SetDepositLimits {
+                    max_total_deposits,
+                    max_per_user,
+                }
+                .data(),
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send set deposit limits txn");
+            let failed = svm
+                .send_transaction(build_deposit_transaction(
+                    &admin,
+                    &mint2022,
+                    token_program,
+                    config,
+                    vault,
+                    user_ata,
+                    svm.latest_blockhash(),
+                ))
+                .expect_err("Deposit above the cap should fail");
+            let code: u32 = error.into();
+            assert!(
+                format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+                "unexpected error: {:?}",
+                failed.err
+            );
+        }
+
+        svm.expire_blockhash();
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetDepositLimits {
+                max_total_deposits: 100,
+                max_per_user: 100,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set deposit limits txn");
+        let tx = svm
+            .send_transaction(build_deposit_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                svm.latest_blockhash(),
+            ))
+            .expect("Deposit at the cap should succeed");
+
+        // Log transaction details
+        msg!("\n\n Capped deposit transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_deposit_fee_collection() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        for token_account in [user_ata, vault] {
+            svm.send_transaction(build_whitelist_transaction(
+                &admin,
+                mint2022.pubkey(),
+                token_account,
+                "add_to_whitelist",
+                recent_blockhash,
+            ))
+            .expect("Failed to send whitelist txn");
+        }
+
+        // 10% on deposits, paid out to the admin's own token account
+        let fee_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::FeeOperations {
+                authority: admin.pubkey(),
+                config,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::SetFees {
+                deposit_fee_bps: 1_000,
+                withdraw_fee_bps: 0,
+                treasury: user_ata,
+            }
+            .data(),
+        };
+        let message = Message::new(&[fee_ix], Some(&admin.pubkey()));
+        svm.send_transaction(Transaction::new(&[&admin], message, recent_blockhash))
+            .expect("Failed to send set fees txn");
+
+        svm.send_transaction(build_deposit_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            recent_blockhash,
+        ))
+        .expect("Failed to send Deposit txn");
+
+        let share_escrow = get_share_escrow_address(config, token_program);
+        let shares_account = svm.get_account(&share_escrow).unwrap();
+        let shares_state = StateWithExtensions::<Account>::unpack(&shares_account.data)
+            .expect("Failed to deserialize share account data");
+        assert_eq!(shares_state.base.amount, 90);
+        assert_eq!(get_config(&svm, config).accrued_fees, 10);
+
+        let tx = svm
+            .send_transaction(build_collect_fees_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                recent_blockhash,
+            ))
+            .expect("Failed to send collect fees txn");
+
+        let vault_account = svm.get_account(&vault).unwrap();
+        let vault_state = StateWithExtensions::<Account>::unpack(&vault_account.data)
+            .expect("Failed to deserialize vault data");
+        assert_eq!(vault_state.base.amount, 90);
+        assert_eq!(get_config(&svm, config).accrued_fees, 0);
+
+        // Log transaction details
+        msg!("\n\n Collect fees transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_withdraw_cooldown() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        let vault_whitelist = get_whitelist_address(mint2022.pubkey(), vault);
+
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        for token_account in [user_ata, vault] {
+            svm.send_transaction(build_whitelist_transaction(
+                &admin,
+                mint2022.pubkey(),
+                token_account,
+                "add_to_whitelist",
+                recent_blockhash,
+            ))
+            .expect("Failed to send whitelist txn");
+        }
+        svm.send_transaction(build_deposit_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            recent_blockhash,
+        ))
+        .expect("Failed to send Deposit txn");
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetWithdrawCooldown {
+                withdraw_cooldown: 3_600,
+            }
+            .data(),
+            recent_blockhash,
+        ))
+        .expect("Failed to send set cooldown txn");
+
+        let failed = svm
+            .send_transaction(build_withdraw_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                vault_whitelist,
+                recent_blockhash,
+            ))
+            .expect_err("Instant withdraw should fail while a cooldown is set");
+        let code: u32 = crate::error::VaultError::CooldownActive.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        // a request can be cancelled and made again
+        svm.send_transaction(build_request_withdraw_transaction(
+            &admin,
+            config,
+            100,
+            recent_blockhash,
+        ))
+        .expect("Failed to send request withdraw txn");
+        svm.send_transaction(build_cancel_withdraw_transaction(
+            &admin,
+            config,
+            recent_blockhash,
+        ))
+        .expect("Failed to send cancel withdraw txn");
+        assert!(svm
+            .get_account(&get_pending_withdrawal_address(&admin, config))
+            .is_none());
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 100);
+
+        svm.expire_blockhash();
+        svm.send_transaction(build_request_withdraw_transaction(
+            &admin,
+            config,
+            100,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send request withdraw txn");
+        let failed = svm
+            .send_transaction(build_claim_withdraw_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                vault_whitelist,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Claiming before the cooldown should fail");
+        let code: u32 = crate::error::VaultError::CooldownNotElapsed.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 3_600;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        let tx = svm
+            .send_transaction(build_claim_withdraw_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                vault_whitelist,
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send claim withdraw txn");
+
+        let vault_account = svm.get_account(&vault).unwrap();
+        let vault_state = StateWithExtensions::<Account>::unpack(&vault_account.data)
+            .expect("Failed to deserialize vault data");
+        assert_eq!(vault_state.base.amount, 0);
+        assert!(svm
+            .get_account(&get_pending_withdrawal_address(&admin, config))
+            .is_none());
+
+        // Log transaction details
+        msg!("\n\n Claim withdraw transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_deposit_locked() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        let vault_whitelist = get_whitelist_address(mint2022.pubkey(), vault);
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        for token_account in [user_ata, vault] {
+            svm.send_transaction(build_whitelist_transaction(
+                &admin,
+                mint2022.pubkey(),
+                token_account,
+                "add_to_whitelist",
+                recent_blockhash,
+            ))
+            .expect("Failed to send whitelist txn");
+        }
+
+        let deposit_locked = crate::instruction::DepositLocked {
+            amount: 100,
+            tier: 1,
+        };
+        let failed = svm
+            .send_transaction(build_deposit_transaction_with_data(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                deposit_locked.data(),
+                recent_blockhash,
+            ))
+            .expect_err("Depositing into a disabled tier should fail");
+        let code: u32 = crate::error::VaultError::InvalidLockupTier.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetLockupTier {
+                index: 1,
+                duration: 86_400,
+                multiplier_bps: 15_000,
+            }
+            .data(),
+            recent_blockhash,
+        ))
+        .expect("Failed to send set lockup tier txn");
+
+        svm.expire_blockhash();
+        let tx = svm
+            .send_transaction(build_deposit_transaction_with_data(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                deposit_locked.data(),
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send locked deposit txn");
+
+        let clock = svm.get_sysvar::<Clock>();
+        let position = crate::Amount::try_deserialize(
+            &mut svm.get_account(&amount_pda).unwrap().data.as_slice(),
+        )
+        .expect("Failed to deserialize position");
+        assert_eq!(position.locked_until, clock.unix_timestamp + 86_400);
+        assert_eq!(position.multiplier_bps, 15_000);
+
+        let failed = svm
+            .send_transaction(build_withdraw_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                vault_whitelist,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Withdrawing a locked position should fail");
+        let code: u32 = crate::error::VaultError::PositionLocked.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        let mut clock = clock;
+        clock.unix_timestamp += 86_400;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        svm.send_transaction(build_withdraw_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            vault_whitelist,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to withdraw after the lock expired");
+
+        // Log transaction details
+        msg!("\n\n Locked deposit transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_claim_rewards() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            user_ata,
+            "add_to_whitelist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send whitelist txn");
+        svm.send_transaction(build_deposit_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            recent_blockhash,
+        ))
+        .expect("Failed to send Deposit txn");
+
+        // plain SPL reward token, so no hook accounts are needed to pay it out
+        let reward_token_program = anchor_spl::token::ID;
+        let reward_mint = CreateMint::new(&mut svm, &admin)
+            .authority(&admin.pubkey())
+            .decimals(6)
+            .send()
+            .expect("Failed to create reward mint");
+        let reward_vault = associated_token::get_associated_token_address_with_program_id(
+            &config,
+            &reward_mint,
+            &reward_token_program,
+        );
+        let user_reward_ata = associated_token::get_associated_token_address_with_program_id(
+            &admin.pubkey(),
+            &reward_mint,
+            &reward_token_program,
+        );
+
+        let configure_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ConfigureRewards {
+                admin: admin.pubkey(),
+                config,
+                reward_mint,
+                reward_vault,
+                reward_token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::ConfigureRewards { reward_rate: 10 }.data(),
+        };
+        let message = Message::new(&[configure_ix], Some(&admin.pubkey()));
+        svm.send_transaction(Transaction::new(&[&admin], message, recent_blockhash))
+            .expect("Failed to send configure rewards txn");
+        MintTo::new(&mut svm, &admin, &reward_mint, &reward_vault, 1_000_000)
+            .send()
+            .expect("Failed to fund the reward vault");
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 100;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        let claim_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ClaimRewards {
+                user: admin.pubkey(),
+                config,
+                amount_pda,
+                reward_mint,
+                reward_vault,
+                user_reward_ata,
+                reward_token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::ClaimRewards {}.data(),
+        };
+        let message = Message::new(&[claim_ix], Some(&admin.pubkey()));
+        let tx = svm
+            .send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to send claim rewards txn");
+
+        // sole staker for 100 seconds at 10 tokens per second
+        let reward_account = svm.get_account(&user_reward_ata).unwrap();
+        let reward_state = StateWithExtensions::<Account>::unpack(&reward_account.data)
+            .expect("Failed to deserialize reward account data");
+        assert_eq!(reward_state.base.amount, 1_000);
+
+        // Log transaction details
+        msg!("\n\n Claim rewards transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_vault_for_existing_spl_mint() {
+        let TestEnv { mut svm, admin, .. } = setup();
+
+        // a classic SPL Token mint created outside the vault, with no transfer hook
+        let token_program = anchor_spl::token::ID;
+        let mint = CreateMint::new(&mut svm, &admin)
+            .authority(&admin.pubkey())
+            .decimals(9)
+            .send()
+            .expect("Failed to create SPL mint");
+        let config = Pubkey::find_program_address(&[b"config", mint.as_ref()], &PROGRAM_ID).0;
+        let vault = associated_token::get_associated_token_address_with_program_id(
+            &config,
+            &mint,
+            &token_program,
+        );
+        let user_ata = associated_token::get_associated_token_address_with_program_id(
+            &admin.pubkey(),
+            &mint,
+            &token_program,
+        );
+
+        let init_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::InitializeForMint {
+                admin: admin.pubkey(),
+                config,
+                mint,
+                share_mint: get_share_mint_address(config),
+                vault,
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::InitializeVaultForMint {}.data(),
+        };
+        let message = Message::new(&[init_ix], Some(&admin.pubkey()));
+        svm.send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to init vault for existing mint");
+        assert_eq!(get_config(&svm, config).decimals, 9);
+
+        // the vault holds no authority over this mint, so fund the user directly
+        CreateAssociatedTokenAccount::new(&mut svm, &admin, &mint)
+            .send()
+            .expect("Failed to create user ata");
+        MintTo::new(&mut svm, &admin, &mint, &user_ata, 1_000_000)
+            .send()
+            .expect("Failed to mint to user ata");
+
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        // no remaining accounts at all: the mint has no TransferHook extension
+        let deposit_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Deposit {
+                user: admin.pubkey(),
+                amount_pda,
+                config,
+                mint,
+                user_ata,
+                vault,
+                share_mint: get_share_mint_address(config),
+                share_escrow: get_share_escrow_address(config, token_program),
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Deposit { amount: 400 }.data(),
+        };
+        let withdraw_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Withdraw {
+                user: admin.pubkey(),
+                amount_pda,
+                config,
+                mint,
+                user_ata,
+                vault,
+                share_mint: get_share_mint_address(config),
+                share_escrow: get_share_escrow_address(config, token_program),
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Withdraw { amount: 150 }.data(),
+        };
+        let message = Message::new(&[deposit_ix, withdraw_ix], Some(&admin.pubkey()));
+        let tx = svm
+            .send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to deposit and withdraw without hook accounts");
+
+        let vault_account = svm.get_account(&vault).unwrap();
+        let vault_state = StateWithExtensions::<Account>::unpack(&vault_account.data)
+            .expect("Failed to deserialize vault data");
+        assert_eq!(vault_state.base.amount, 250);
+
+        // Log transaction details
+        msg!("\n\n Existing SPL mint deposit/withdraw successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_deposit_requires_hook_accounts() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            user_ata,
+            "add_to_whitelist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send whitelist txn");
+
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        // the mint carries a transfer hook, but no remaining accounts are passed
+        let deposit_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Deposit {
+                user: admin.pubkey(),
+                amount_pda,
+                config,
+                mint: mint2022.pubkey(),
+                user_ata,
+                vault,
+                share_mint: get_share_mint_address(config),
+                share_escrow: get_share_escrow_address(config, token_program),
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Deposit { amount: 100 }.data(),
+        };
+        let message = Message::new(&[deposit_ix.clone()], Some(&admin.pubkey()));
+        let failed = svm
+            .send_transaction(Transaction::new(&[&admin], message, recent_blockhash))
+            .expect_err("Deposit without the hook accounts should fail");
+        let code: u32 = crate::error::VaultError::MissingHookAccounts.into();
+        assert!(format!("{:?}", failed.err).contains(&format!("Custom({})", code)));
+
+        // a meta list derived for another mint is rejected before any CPI
+        let user_whitelist = get_whitelist_address(mint2022.pubkey(), user_ata);
+        let mut accounts = deposit_ix.accounts.clone();
+        accounts.extend(get_hook_account_metas(
+            Pubkey::new_unique(),
+            user_whitelist,
+            get_whitelist_address(mint2022.pubkey(), vault),
+        ));
+        let wrong_meta_list_ix = Instruction {
+            accounts,
+            ..deposit_ix.clone()
+        };
+        let message = Message::new(&[wrong_meta_list_ix], Some(&admin.pubkey()));
+        let failed = svm
+            .send_transaction(Transaction::new(&[&admin], message, recent_blockhash))
+            .expect_err("Deposit with a foreign meta list should fail");
+        let code: u32 = crate::error::VaultError::InvalidExtraAccountMetaList.into();
+        assert!(format!("{:?}", failed.err).contains(&format!("Custom({})", code)));
+
+        // the same deposit resolves once the hook accounts are supplied
+        let tx = svm
+            .send_transaction(build_deposit_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                recent_blockhash,
+            ))
+            .expect("Failed to send Deposit txn");
+
+        // Log transaction details
+        msg!("\n\n Deposit with resolved hook accounts successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_deposit_with_transfer_fee() {
+        let TestEnv { mut svm, admin, .. } = setup();
+
+        // Token-2022 mint charging 1% on every transfer, no transfer hook
+        let token_program = anchor_spl::token_2022::ID;
+        let mint = Keypair::new();
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+                .unwrap();
+        svm.set_account(
+            mint.pubkey(),
+            SolanaAccount {
+                lamports: svm.minimum_balance_for_rent_exemption(space),
+                data: vec![0; space],
+                owner: token_program,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .expect("Failed to allocate the mint account");
+        let init_fee_ix = initialize_transfer_fee_config(
+            &token_program,
+            &mint.pubkey(),
+            Some(&admin.pubkey()),
+            Some(&admin.pubkey()),
+            100,
+            u64::MAX,
+        )
+        .unwrap();
+        // the vault config must be the mint authority to mint through the program
+        let config =
+            Pubkey::find_program_address(&[b"config", mint.pubkey().as_ref()], &PROGRAM_ID).0;
+        let init_mint_ix =
+            initialize_mint2(&token_program, &mint.pubkey(), &config, None, 6).unwrap();
+
+        let mint = mint.pubkey();
+        let vault = associated_token::get_associated_token_address_with_program_id(
+            &config,
+            &mint,
+            &token_program,
+        );
+        let user_ata = associated_token::get_associated_token_address_with_program_id(
+            &admin.pubkey(),
+            &mint,
+            &token_program,
+        );
+        let init_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::InitializeForMint {
+                admin: admin.pubkey(),
+                config,
+                mint,
+                share_mint: get_share_mint_address(config),
+                vault,
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::InitializeVaultForMint {}.data(),
+        };
+        let mint_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::MintToken {
+                minter: admin.pubkey(),
+                user: admin.pubkey(),
+                config,
+                mint,
+                user_ata,
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Mint { amount: 1_000_000 }.data(),
+        };
+        let message = Message::new(
+            &[init_fee_ix, init_mint_ix, init_ix, mint_ix],
+            Some(&admin.pubkey()),
+        );
+        svm.send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to set up the transfer-fee vault");
+
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        let deposit_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Deposit {
+                user: admin.pubkey(),
+                amount_pda,
+                config,
+                mint,
+                user_ata,
+                vault,
+                share_mint: get_share_mint_address(config),
+                share_escrow: get_share_escrow_address(config, token_program),
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Deposit { amount: 10_000 }.data(),
+        };
+        let message = Message::new(&[deposit_ix], Some(&admin.pubkey()));
+        svm.send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to send Deposit txn");
+
+        // 1% of 10_000 is withheld on the vault ATA and never credited
+        let amount_account = svm.get_account(&amount_pda).unwrap();
+        let position = crate::Amount::try_deserialize(&mut amount_account.data.as_slice())
+            .expect("Failed to deserialize Amount");
+        assert_eq!(position.shares, 9_900);
+
+        let harvest_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::HarvestWithheldFees {
+                admin: admin.pubkey(),
+                config,
+                mint,
+                vault,
+                token_program,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::HarvestWithheldFees {}.data(),
+        };
+        let message = Message::new(&[harvest_ix], Some(&admin.pubkey()));
+        let tx = svm
+            .send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to harvest withheld fees");
+
+        let mint_account = svm.get_account(&mint).unwrap();
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+            .expect("Failed to deserialize mint data");
+        let fee_config = mint_state
+            .get_extension::<TransferFeeConfig>()
+            .expect("Mint has no transfer fee config");
+        assert_eq!(u64::from(fee_config.withheld_amount), 100);
+
+        // Log transaction details
+        msg!("\n\n Harvest withheld fees transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_update_metadata() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata: _,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+
+        let contains = |svm: &LiteSVM, needle: &[u8]| {
+            svm.get_account(&mint2022.pubkey())
+                .unwrap()
+                .data
+                .windows(needle.len())
+                .any(|window| window == needle)
+        };
+        assert!(contains(&svm, b"Vault Token"));
+
+        let update_ix = |signer: &Keypair| Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::UpdateMetadata {
+                admin: signer.pubkey(),
+                config,
+                mint: mint2022.pubkey(),
+                token_program,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::UpdateMetadata {
+                field: crate::MetadataField::Name,
+                value: "Renamed Vault Token".to_string(),
+            }
+            .data(),
+        };
+
+        let stranger = Keypair::new();
+        svm.airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to stranger.");
+        let message = Message::new(&[update_ix(&stranger)], Some(&stranger.pubkey()));
+        svm.send_transaction(Transaction::new(&[&stranger], message, recent_blockhash))
+            .expect_err("Only the admin can update metadata");
+
+        let message = Message::new(&[update_ix(&admin)], Some(&admin.pubkey()));
+        let tx = svm
+            .send_transaction(Transaction::new(&[&admin], message, recent_blockhash))
+            .expect("Failed to send update metadata txn");
+        assert!(contains(&svm, b"Renamed Vault Token"));
+
+        // Log transaction details
+        msg!("\n\n Update metadata transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_minter_allow_list() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+
+        let minter = Keypair::new();
+        svm.airdrop(&minter.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to minter.");
+        let minter_ata = associated_token::get_associated_token_address_with_program_id(
+            &minter.pubkey(),
+            &mint2022.pubkey(),
+            &token_program,
+        );
+        let mint_ix = |user: Pubkey, user_ata: Pubkey, amount: u64| Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::MintToken {
+                minter: minter.pubkey(),
+                user,
+                config,
+                mint: mint2022.pubkey(),
+                user_ata,
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Mint { amount }.data(),
+        };
+
+        let message = Message::new(
+            &[mint_ix(minter.pubkey(), minter_ata, 500)],
+            Some(&minter.pubkey()),
+        );
+        svm.send_transaction(Transaction::new(&[&minter], message, recent_blockhash))
+            .expect_err("Accounts off the allow-list cannot mint");
+
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::AddMinter {
+                minter: minter.pubkey(),
+            }
+            .data(),
+            recent_blockhash,
+        ))
+        .expect("Failed to add minter");
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetMaxSupply { max_supply: 500 }.data(),
+            recent_blockhash,
+        ))
+        .expect("Failed to set max supply");
+        svm.expire_blockhash();
+
+        let message = Message::new(
+            &[mint_ix(minter.pubkey(), minter_ata, 500)],
+            Some(&minter.pubkey()),
+        );
+        let tx = svm
+            .send_transaction(Transaction::new(
+                &[&minter],
+                message,
+                svm.latest_blockhash(),
+            ))
+            .expect("Allow-listed minter should be able to mint");
+
+        // the mint authority is the config PDA, not the admin key
+        let mint_account = svm.get_account(&mint2022.pubkey()).unwrap();
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+            .expect("Failed to deserialize mint data");
+        assert_eq!(mint_state.base.mint_authority, Some(config).into());
+        assert_eq!(mint_state.base.supply, 500);
+
+        let message = Message::new(
+            &[mint_ix(admin.pubkey(), user_ata, 1)],
+            Some(&minter.pubkey()),
+        );
+        svm.send_transaction(Transaction::new(
+            &[&minter],
+            message,
+            svm.latest_blockhash(),
+        ))
+        .expect_err("Minting past the max supply should fail");
+
+        // Log transaction details
+        msg!("\n\n Allow-listed mint transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_batch_mint() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        svm.expire_blockhash();
+
+        // the ATA exists now, minting to it again must still work
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to mint to an existing ata");
+
+        let recipient = Keypair::new();
+        let recipient_ata = associated_token::get_associated_token_address_with_program_id(
+            &recipient.pubkey(),
+            &mint2022.pubkey(),
+            &token_program,
+        );
+        let create_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::MintToken {
+                minter: admin.pubkey(),
+                user: recipient.pubkey(),
+                config,
+                mint: mint2022.pubkey(),
+                user_ata: recipient_ata,
+                token_program,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Mint { amount: 10 }.data(),
+        };
+
+        let mut accounts = crate::accounts::BatchMint {
+            minter: admin.pubkey(),
+            config,
+            mint: mint2022.pubkey(),
+            token_program,
+        }
+        .to_account_metas(None);
+        accounts.push(AccountMeta::new(user_ata, false));
+        accounts.push(AccountMeta::new(recipient_ata, false));
+        let batch_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::BatchMint {
+                amounts: vec![100, 200],
+            }
+            .data(),
+        };
+
+        let message = Message::new(&[create_ix, batch_ix], Some(&admin.pubkey()));
+        let tx = svm
+            .send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to send batch mint txn");
+
+        for (token_account, expected) in [(user_ata, 2_100), (recipient_ata, 210)] {
+            let account = svm.get_account(&token_account).unwrap();
+            let state = StateWithExtensions::<Account>::unpack(&account.data)
+                .expect("Failed to deserialize token account data");
+            assert_eq!(state.base.amount, expected);
+        }
+
+        // Log transaction details
+        msg!("\n\n Batch mint transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_whitelist_requires_hook_authority() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata: _,
+        } = setup();
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+
+        // only the whitelist admin may create a hook config and its authority
+        let stranger = Keypair::new();
+        svm.airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to stranger.");
+        let failed = svm
+            .send_transaction(build_init_tf_transaction(
+                &stranger,
+                &mint2022,
+                recent_blockhash,
+            ))
+            .expect_err("Only the whitelist admin may initialize the hook");
+        // WhitelistError::NotAdmin
+        assert!(format!("{:?}", failed.err).contains("Custom(6003)"));
+
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+
+        // the admin that created the hook config is its first authority
+        let hook_config = svm
+            .get_account(&get_hook_config_address(mint2022.pubkey()))
+            .unwrap();
+        assert_eq!(&hook_config.data[8..40], mint2022.pubkey().as_ref());
+        assert_eq!(&hook_config.data[40..72], admin.pubkey().as_ref());
+
+        let failed = svm
+            .send_transaction(build_whitelist_transaction(
+                &stranger,
+                mint2022.pubkey(),
+                stranger.pubkey(),
+                "add_to_whitelist",
+                recent_blockhash,
+            ))
+            .expect_err("Only the hook config authority may whitelist");
+        // WhitelistError::NotAuthority
+        assert!(format!("{:?}", failed.err).contains("Custom(6004)"));
+
+        let tx = svm
+            .send_transaction(build_whitelist_transaction(
+                &admin,
+                mint2022.pubkey(),
+                vault,
+                "add_to_whitelist",
+                recent_blockhash,
+            ))
+            .expect("Failed to send whitelist txn");
+        assert!(svm
+            .get_account(&get_whitelist_address(mint2022.pubkey(), vault))
+            .is_some());
+
+        // Log transaction details
+        msg!("\n\n Whitelist gated on the hook config authority");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_set_whitelist_admin() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata: _,
+        } = setup();
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+
+        let new_admin = Keypair::new();
+        svm.airdrop(&new_admin.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to new admin.");
+
+        // the admin record is created once, by the upgrade authority
+        svm.send_transaction(build_init_whitelist_admin_transaction(
+            &new_admin,
+            new_admin.pubkey(),
+            recent_blockhash,
+        ))
+        .expect_err("The whitelist admin can only be initialized once");
+
+        let failed = svm
+            .send_transaction(build_set_whitelist_admin_transaction(
+                &new_admin,
+                new_admin.pubkey(),
+                recent_blockhash,
+            ))
+            .expect_err("Only the whitelist admin may rotate it");
+        // WhitelistError::NotAdmin
+        assert!(format!("{:?}", failed.err).contains("Custom(6003)"));
+
+        let tx = svm
+            .send_transaction(build_set_whitelist_admin_transaction(
+                &admin,
+                new_admin.pubkey(),
+                recent_blockhash,
+            ))
+            .expect("Failed to rotate the whitelist admin");
+        let whitelist_admin = svm.get_account(&get_whitelist_admin_address()).unwrap();
+        assert_eq!(&whitelist_admin.data[8..40], new_admin.pubkey().as_ref());
+
+        // the admin does not manage lists itself, it appoints who does
+        let failed = svm
+            .send_transaction(build_whitelist_transaction(
+                &new_admin,
+                mint2022.pubkey(),
+                vault,
+                "add_to_whitelist",
+                recent_blockhash,
+            ))
+            .expect_err("The whitelist admin is not the hook config authority");
+        // WhitelistError::NotAuthority
+        assert!(format!("{:?}", failed.err).contains("Custom(6004)"));
+
+        let failed = svm
+            .send_transaction(build_set_hook_authority_transaction(
+                &admin,
+                mint2022.pubkey(),
+                admin.pubkey(),
+                recent_blockhash,
+            ))
+            .expect_err("A former admin may not appoint hook authorities");
+        // WhitelistError::NotAdmin
+        assert!(format!("{:?}", failed.err).contains("Custom(6003)"));
+
+        svm.send_transaction(build_set_hook_authority_transaction(
+            &new_admin,
+            mint2022.pubkey(),
+            new_admin.pubkey(),
+            recent_blockhash,
+        ))
+        .expect("Failed to rotate the hook config authority");
+        svm.expire_blockhash();
+        svm.send_transaction(build_whitelist_transaction(
+            &new_admin,
+            mint2022.pubkey(),
+            vault,
+            "add_to_whitelist",
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send whitelist txn");
+
+        // Log transaction details
+        msg!("\n\n Set whitelist admin transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_whitelist_by_owner() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+
+        // only the hook config authority may switch modes
+        let stranger = Keypair::new();
+        svm.airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to stranger.");
+        let failed = svm
+            .send_transaction(build_hook_config_transaction(
+                &stranger,
+                mint2022.pubkey(),
+                "set_hook_mode",
+                1,
+                recent_blockhash,
+            ))
+            .expect_err("Only the hook config authority may set the mode");
+        // WhitelistError::NotAuthority
+        assert!(format!("{:?}", failed.err).contains("Custom(6004)"));
+
+        svm.send_transaction(build_hook_config_transaction(
+            &admin,
+            mint2022.pubkey(),
+            "set_hook_mode",
+            1,
+            recent_blockhash,
+        ))
+        .expect("Failed to switch to owner mode");
+
+        // entries are now derived from the source token account's owner
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        let mut accounts = crate::accounts::Deposit {
+            user: admin.pubkey(),
+            user_ata,
+            config,
+            amount_pda,
+            vault,
+            mint: mint2022.pubkey(),
+            share_mint: get_share_mint_address(config),
+            share_escrow: get_share_escrow_address(config, token_program),
+            associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+            token_program,
+            system_program: SYSTEM_PROGRAM,
+        }
+        .to_account_metas(None);
+        accounts.extend(get_hook_account_metas(
+            mint2022.pubkey(),
+            get_whitelist_address(mint2022.pubkey(), admin.pubkey()),
+            get_whitelist_address(mint2022.pubkey(), config),
+        ));
+        let deposit_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::Deposit { amount: 100 }.data(),
+        };
+
+        let message = Message::new(&[deposit_ix.clone()], Some(&admin.pubkey()));
+        svm.send_transaction(Transaction::new(&[&admin], message, recent_blockhash))
+            .expect_err("Deposit from a non-whitelisted owner should fail");
+
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            admin.pubkey(),
+            "add_to_whitelist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send whitelist txn");
+
+        svm.expire_blockhash();
+        let message = Message::new(&[deposit_ix], Some(&admin.pubkey()));
+        let tx = svm
+            .send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to send Deposit txn");
+
+        let vault_account = svm.get_account(&vault).unwrap();
+        let vault_state = StateWithExtensions::<Account>::unpack(&vault_account.data)
+            .expect("Failed to deserialize vault data");
+        assert_eq!(vault_state.base.amount, 100);
+
+        // Log transaction details
+        msg!("\n\n Deposit whitelisted by owner successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_destination_check() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            user_ata,
+            "add_to_whitelist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send whitelist txn");
+
+        let failed = svm
+            .send_transaction(build_hook_config_transaction(
+                &admin,
+                mint2022.pubkey(),
+                "set_check_policy",
+                0,
+                recent_blockhash,
+            ))
+            .expect_err("A policy must check at least one side");
+        // WhitelistError::InvalidCheckPolicy
+        assert!(format!("{:?}", failed.err).contains("Custom(6006)"));
+
+        // check both the sender and the receiver
+        svm.send_transaction(build_hook_config_transaction(
+            &admin,
+            mint2022.pubkey(),
+            "set_check_policy",
+            3,
+            recent_blockhash,
+        ))
+        .expect("Failed to set the check policy");
+
+        let failed = svm
+            .send_transaction(build_deposit_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                recent_blockhash,
+            ))
+            .expect_err("Deposit into a non-whitelisted vault should fail");
+        // WhitelistError::NotWhitelisted
+        assert!(format!("{:?}", failed.err).contains("Custom(6000)"));
+
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            vault,
+            "add_to_whitelist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send whitelist txn");
+
+        svm.expire_blockhash();
+        let tx = svm
+            .send_transaction(build_deposit_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send Deposit txn");
+
+        let vault_account = svm.get_account(&vault).unwrap();
+        let vault_state = StateWithExtensions::<Account>::unpack(&vault_account.data)
+            .expect("Failed to deserialize vault data");
+        assert_eq!(vault_state.base.amount, 100);
+
+        // Log transaction details
+        msg!("\n\n Deposit with destination check successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_deny_list() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+        svm.send_transaction(build_init_tf_transaction(
+            &admin,
+            &mint2022,
+            recent_blockhash,
+        ))
+        .expect("Failed to send init tf hoook tx");
+        svm.send_transaction(build_mint_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            user_ata,
+            1_000_000,
+            recent_blockhash,
+        ))
+        .expect("Failed to send mint txn");
+
+        // open by default: nothing is whitelisted, only blocked entries reject
+        svm.send_transaction(build_hook_config_transaction(
+            &admin,
+            mint2022.pubkey(),
+            "set_deny_list",
+            1,
+            recent_blockhash,
+        ))
+        .expect("Failed to enable the deny list");
+
+        let amount_pda = Pubkey::find_program_address(
+            &[b"amount", config.as_ref(), admin.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        let mut accounts = crate::accounts::Deposit {
+            user: admin.pubkey(),
+            user_ata,
+            config,
+            amount_pda,
+            vault,
+            mint: mint2022.pubkey(),
+            share_mint: get_share_mint_address(config),
+            share_escrow: get_share_escrow_address(config, token_program),
+            associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+            token_program,
+            system_program: SYSTEM_PROGRAM,
+        }
+        .to_account_metas(None);
+        accounts.extend(get_hook_account_metas(
+            mint2022.pubkey(),
+            get_blocked_address(mint2022.pubkey(), user_ata),
+            get_blocked_address(mint2022.pubkey(), vault),
+        ));
+        let deposit_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::Deposit { amount: 100 }.data(),
+        };
+
+        let message = Message::new(&[deposit_ix.clone()], Some(&admin.pubkey()));
+        svm.send_transaction(Transaction::new(&[&admin], message, recent_blockhash))
+            .expect("Deposit from an unblocked account should succeed");
+
+        svm.send_transaction(build_blocklist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            user_ata,
+            "add_to_blocklist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send blocklist txn");
+
+        svm.expire_blockhash();
+        let recent_blockhash = svm.latest_blockhash();
+        let message = Message::new(&[deposit_ix.clone()], Some(&admin.pubkey()));
+        let failed = svm
+            .send_transaction(Transaction::new(&[&admin], message, recent_blockhash))
+            .expect_err("Deposit from a blocked account should fail");
+        // WhitelistError::Blocked
+        assert!(format!("{:?}", failed.err).contains("Custom(6007)"));
+
+        svm.send_transaction(build_blocklist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            user_ata,
+            "remove_from_blocklist",
+            recent_blockhash,
+        ))
+        .expect("Failed to send remove from blocklist txn");
+        assert!(svm
+            .get_account(&get_blocked_address(mint2022.pubkey(), user_ata))
+            .is_none_or(|account| account.data.is_empty()));
+
+        svm.expire_blockhash();
+        let recent_blockhash = svm.latest_blockhash();
+        let message = Message::new(&[deposit_ix], Some(&admin.pubkey()));
+        let tx = svm
+            .send_transaction(Transaction::new(&[&admin], message, recent_blockhash))
+            .expect("Failed to send Deposit txn");
+
+        let vault_account = svm.get_account(&vault).unwrap();
+        let vault_state = StateWithExtensions::<Account>::unpack(&vault_account.data)
+            .expect("Failed to deserialize vault data");
+        assert_eq!(vault_state.base.amount, 200);
+
+        // Log transaction details
+        msg!("\n\n Deposit with deny list successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_transferred_shares_earn_rewards() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+        let reward_mint = setup_rewards(&mut svm, &admin, config, 10);
+
+        let holder = Keypair::new();
+        svm.airdrop(&holder.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to holder.");
+
+        // the depositor hands every share to someone else
+        let transaction =
+            build_unstake_transaction(&admin, config, token_program, 100, svm.latest_blockhash());
+        svm.send_transaction(transaction)
+            .expect("Failed to send unstake txn");
+        let transaction = build_share_transfer_transaction(
+            &admin,
+            holder.pubkey(),
+            config,
+            token_program,
+            100,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to transfer shares");
+        let transaction = build_stake_transaction(
+            &holder,
+            config,
+            vault,
+            token_program,
+            100,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send stake txn");
+
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 0);
+        assert_eq!(get_position(&svm, holder.pubkey(), config).shares, 100);
+        assert_eq!(get_config(&svm, config).total_stake, 100);
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 100;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        // the original depositor no longer holds the shares, so earns nothing
+        let transaction = build_claim_rewards_transaction(
+            &admin,
+            config,
+            reward_mint,
+            anchor_spl::token::ID,
+            svm.latest_blockhash(),
+        );
+        let failed = svm
+            .send_transaction(transaction)
+            .expect_err("A position without shares must not earn rewards");
+        let code: u32 = crate::error::VaultError::NoRewardsToClaim.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        let transaction = build_claim_rewards_transaction(
+            &holder,
+            config,
+            reward_mint,
+            anchor_spl::token::ID,
+            svm.latest_blockhash(),
+        );
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to send claim rewards txn");
+
+        // sole staker for 100 seconds at 10 tokens per second
+        assert_eq!(reward_balance(&svm, holder.pubkey(), reward_mint), 1_000);
+
+        // Log transaction details
+        msg!("\n\n Transferred shares claim successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_locked_shares_stay_in_escrow() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetLockupTier {
+                index: 1,
+                duration: 86_400,
+                multiplier_bps: 15_000,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set lockup tier txn");
+        svm.send_transaction(build_deposit_transaction_with_data(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            crate::instruction::DepositLocked {
+                amount: 100,
+                tier: 1,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send locked deposit txn");
+
+        // moving the shares to a second wallet would let them be redeemed there
+        let holder = Keypair::new();
+        for shares in [200, 1] {
+            svm.expire_blockhash();
+            let transaction = build_unstake_transaction(
+                &admin,
+                config,
+                token_program,
+                shares,
+                svm.latest_blockhash(),
+            );
+            let failed = svm
+                .send_transaction(transaction)
+                .expect_err("Unstaking a locked position should fail");
+            let code: u32 = crate::error::VaultError::PositionLocked.into();
+            assert!(
+                format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+                "unexpected error: {:?}",
+                failed.err
+            );
+        }
+        assert!(svm
+            .get_account(&get_user_shares_address(
+                admin.pubkey(),
+                config,
+                token_program
+            ))
+            .is_none());
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 200);
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 86_400;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        let transaction =
+            build_unstake_transaction(&admin, config, token_program, 200, svm.latest_blockhash());
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to unstake after the lock expired");
+        let transaction = build_share_transfer_transaction(
+            &admin,
+            holder.pubkey(),
+            config,
+            token_program,
+            200,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to transfer unlocked shares");
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 0);
+
+        // Log transaction details
+        msg!("\n\n Unstake after lock transaction successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_expired_lock_stops_boost() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+        let reward_mint = setup_rewards(&mut svm, &admin, config, 10);
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetLockupTier {
+                index: 1,
+                duration: 100,
+                multiplier_bps: 20_000,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set lockup tier txn");
+
+        // a second, unboosted staker holding 50 shares
+        let holder = Keypair::new();
+        svm.airdrop(&holder.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to holder.");
+        let transaction =
+            build_unstake_transaction(&admin, config, token_program, 50, svm.latest_blockhash());
+        svm.send_transaction(transaction)
+            .expect("Failed to send unstake txn");
+        let transaction = build_share_transfer_transaction(
+            &admin,
+            holder.pubkey(),
+            config,
+            token_program,
+            50,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to transfer shares");
+        let transaction = build_stake_transaction(
+            &holder,
+            config,
+            vault,
+            token_program,
+            50,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send stake txn");
+
+        // 100 shares locked at 2x for 100 seconds
+        svm.send_transaction(build_deposit_transaction_with_data(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            crate::instruction::DepositLocked {
+                amount: 50,
+                tier: 1,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send locked deposit txn");
+        assert_eq!(get_config(&svm, config).total_stake, 250);
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 200;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        let transaction = build_claim_rewards_transaction(
+            &admin,
+            config,
+            reward_mint,
+            anchor_spl::token::ID,
+            svm.latest_blockhash(),
+        );
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to send claim rewards txn");
+
+        // 200/250 of the first 100 seconds, then 100/150 of the next 100
+        assert_eq!(reward_balance(&svm, admin.pubkey(), reward_mint), 1_466);
+        let position = get_position(&svm, admin.pubkey(), config);
+        assert_eq!(position.multiplier_bps, crate::BPS_DENOMINATOR);
+        assert_eq!(position.stake, 100);
+        assert_eq!(get_config(&svm, config).total_stake, 150);
+
+        let transaction = build_claim_rewards_transaction(
+            &holder,
+            config,
+            reward_mint,
+            anchor_spl::token::ID,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send claim rewards txn");
+        assert_eq!(reward_balance(&svm, holder.pubkey(), reward_mint), 533);
+
+        // Log transaction details
+        msg!("\n\n Expired lock claim successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_reward_mint_must_differ_from_vault_mints() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata: _,
+        } = setup();
+
+        let recent_blockhash = svm.latest_blockhash();
+        svm.send_transaction(build_init_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            recent_blockhash,
+        ))
+        .expect("Failed to send vault init tx");
+
+        // paying rewards from the vault or share mint would drain depositors
+        for (reward_mint, error) in [
+            (
+                mint2022.pubkey(),
+                crate::error::VaultError::RewardMintIsVaultMint,
+            ),
+            (
+                get_share_mint_address(config),
+                crate::error::VaultError::RewardMintIsShareMint,
+            ),
+        ] {
+            let failed = svm
+                .send_transaction(build_configure_rewards_transaction(
+                    &admin,
+                    config,
+                    reward_mint,
+                    token_program,
+                    10,
+                    recent_blockhash,
+                ))
+                .expect_err("Configuring a vault mint as reward mint should fail");
+            let code: u32 = error.into();
+            assert!(
+                format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+                "unexpected error: {:?}",
+                failed.err
+            );
+        }
+
+        let reward_mint = setup_rewards(&mut svm, &admin, config, 10);
+        assert_eq!(get_config(&svm, config).reward_mint, reward_mint);
+    }
+
+    #[test]
+    fn test_withdraw_queue_redeems_shares() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        let vault_whitelist = get_whitelist_address(mint2022.pubkey(), vault);
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetWithdrawCooldown {
+                withdraw_cooldown: 3_600,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set cooldown txn");
+
+        // a plain transfer into the vault doubles the value of every share
+        let mut donate_ix = spl_token_2022::instruction::transfer_checked(
+            &token_program,
+            &user_ata,
+            &mint2022.pubkey(),
+            &vault,
+            &admin.pubkey(),
+            &[],
+            100,
+            6,
+        )
+        .unwrap();
+        donate_ix.accounts.extend(get_hook_account_metas(
+            mint2022.pubkey(),
+            get_whitelist_address(mint2022.pubkey(), user_ata),
+            vault_whitelist,
+        ));
+        let message = Message::new(&[donate_ix], Some(&admin.pubkey()));
+        svm.send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to donate to the vault");
+
+        // the holder never deposited, they only received shares
+        let holder = Keypair::new();
+        svm.airdrop(&holder.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to holder.");
+        let holder_ata = associated_token::get_associated_token_address_with_program_id(
+            &holder.pubkey(),
+            &mint2022.pubkey(),
+            &token_program,
+        );
+        let create_ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &holder.pubkey(),
+            &holder.pubkey(),
+            &mint2022.pubkey(),
+            &token_program,
+        );
+        let message = Message::new(&[create_ix], Some(&holder.pubkey()));
+        svm.send_transaction(Transaction::new(
+            &[&holder],
+            message,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to create holder ata");
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            mint2022.pubkey(),
+            holder_ata,
+            "add_to_whitelist",
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to whitelist holder ata");
+
+        let transaction =
+            build_unstake_transaction(&admin, config, token_program, 50, svm.latest_blockhash());
+        svm.send_transaction(transaction)
+            .expect("Failed to send unstake txn");
+        let transaction = build_share_transfer_transaction(
+            &admin,
+            holder.pubkey(),
+            config,
+            token_program,
+            50,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to transfer shares");
+        let transaction = build_stake_transaction(
+            &holder,
+            config,
+            vault,
+            token_program,
+            50,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send stake txn");
+
+        svm.send_transaction(build_request_withdraw_transaction(
+            &holder,
+            config,
+            50,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send request withdraw txn");
+        assert_eq!(get_position(&svm, holder.pubkey(), config).shares, 0);
+
+        // requested shares can no longer leave through the position
+        let transaction =
+            build_unstake_transaction(&holder, config, token_program, 50, svm.latest_blockhash());
+        let failed = svm
+            .send_transaction(transaction)
+            .expect_err("Unstaking requested shares should fail");
+        let code: u32 = crate::error::VaultError::AmountExceededUrDeposit.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 3_600;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        let tx = svm
+            .send_transaction(build_claim_withdraw_transaction(
+                &holder,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                holder_ata,
+                vault_whitelist,
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send claim withdraw txn");
+
+        // 50 of 100 shares against 200 assets
+        let holder_account = svm.get_account(&holder_ata).unwrap();
+        let holder_state = StateWithExtensions::<Account>::unpack(&holder_account.data)
+            .expect("Failed to deserialize holder ata data");
+        assert_eq!(holder_state.base.amount, 100);
+        assert!(svm
+            .get_account(&get_pending_withdrawal_address(&holder, config))
+            .is_none());
+
+        // Log transaction details
+        msg!("\n\n Queued share claim successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_init_vault_with_decimals() {
+        for decimals in [0u8, 9] {
+            let TestEnv {
+                mut svm,
+                admin,
+                mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+            } = setup();
+
+            let recent_blockhash = svm.latest_blockhash();
+            svm.send_transaction(build_init_transaction_with_decimals(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                decimals,
+                recent_blockhash,
+            ))
+            .expect("Failed to send vault init tx");
+            svm.send_transaction(build_init_tf_transaction(
+                &admin,
+                &mint2022,
+                recent_blockhash,
+            ))
+            .expect("Failed to send init tf hoook tx");
+            svm.send_transaction(build_mint_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                user_ata,
+                1_000_000,
+                recent_blockhash,
+            ))
+            .expect("Failed to send mint txn");
+            for account in [user_ata, vault] {
+                svm.send_transaction(build_whitelist_transaction(
+                    &admin,
+                    mint2022.pubkey(),
+                    account,
+                    "add_to_whitelist",
+                    recent_blockhash,
+                ))
+                .expect("Failed to send whitelist txn");
+            }
+
+            // the vault and share mint follow the requested decimals
+            assert_eq!(get_config(&svm, config).decimals, decimals);
+            for mint in [mint2022.pubkey(), get_share_mint_address(config)] {
+                let mint_account = svm.get_account(&mint).unwrap();
+                let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+                    .expect("Failed to deserialize mint data");
+                assert_eq!(mint_state.base.decimals, decimals);
+            }
+
+            let tx = svm
+                .send_transaction(build_deposit_transaction(
+                    &admin,
+                    &mint2022,
+                    token_program,
+                    config,
+                    vault,
+                    user_ata,
+                    recent_blockhash,
+                ))
+                .expect("Failed to send Deposit txn");
+            assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 100);
+
+            // Log transaction details
+            msg!("\n\n Deposit with {} decimals successful", decimals);
+            msg!("CUs Consumed: {}", tx.compute_units_consumed);
+            msg!("Tx Signature: {}", tx.signature);
+        }
+    }
+
+    #[test]
+    fn test_mismatched_decimals_rejected() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
         } = setup();
+
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+
+        // point the config at decimals the mint does not have
+        let mut account = svm.get_account(&config).unwrap();
+        let mut config_state = get_config(&svm, config);
+        config_state.decimals = 9;
+        config_state
+            .try_serialize(&mut account.data.as_mut_slice())
+            .expect("Failed to serialize config");
+        svm.set_account(config, account)
+            .expect("Failed to overwrite config");
+
         let recent_blockhash = svm.latest_blockhash();
-        let transaction = build_init_transaction(
+        let code: u32 = anchor_lang::error::ErrorCode::ConstraintMintDecimals.into();
+        let transactions = [
+            (
+                "deposit",
+                build_deposit_transaction(
+                    &admin,
+                    &mint2022,
+                    token_program,
+                    config,
+                    vault,
+                    user_ata,
+                    recent_blockhash,
+                ),
+            ),
+            (
+                "withdraw",
+                build_withdraw_transaction(
+                    &admin,
+                    &mint2022,
+                    token_program,
+                    config,
+                    vault,
+                    user_ata,
+                    get_whitelist_address(mint2022.pubkey(), vault),
+                    recent_blockhash,
+                ),
+            ),
+            (
+                "mint",
+                build_mint_transaction(
+                    &admin,
+                    &mint2022,
+                    token_program,
+                    config,
+                    user_ata,
+                    1_000,
+                    recent_blockhash,
+                ),
+            ),
+        ];
+        for (name, transaction) in transactions {
+            let failed = svm
+                .send_transaction(transaction)
+                .expect_err("Instruction with mismatched decimals should fail");
+            assert!(
+                format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+                "unexpected {} error: {:?}",
+                name,
+                failed.err
+            );
+        }
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 100);
+    }
+
+    #[test]
+    fn test_deposit_rejects_wrong_hook_program() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+
+        // the remaining accounts name an executable program that is not the mint's hook
+        let mut accounts =
+            get_deposit_account_metas(&admin, &mint2022, token_program, config, vault, user_ata);
+        let hook_program_index = accounts.len() - 5;
+        assert_eq!(
+            accounts[hook_program_index].pubkey,
+            get_tf_hook_program_address()
+        );
+        accounts[hook_program_index] = AccountMeta::new_readonly(PROGRAM_ID, false);
+        let deposit_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::Deposit { amount: 100 }.data(),
+        };
+        let message = Message::new(&[deposit_ix], Some(&admin.pubkey()));
+        let failed = svm
+            .send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect_err("Deposit with the wrong hook program should fail");
+        let code: u32 = crate::error::VaultError::HookProgramMismatch.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 100);
+    }
+
+    #[test]
+    fn test_hook_program_must_be_executable() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        // a plain system account cannot be set as the mint's hook program
+        let not_a_program = Keypair::new();
+        svm.airdrop(&not_a_program.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to the fake hook program.");
+        let init_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Initialize {
+                admin: admin.pubkey(),
+                config,
+                vault,
+                mint: mint2022.pubkey(),
+                share_mint: get_share_mint_address(config),
+                transfer_hook_program: not_a_program.pubkey(),
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+                token_program,
+            }
+            .to_account_metas(Some(true)),
+            data: crate::instruction::InitializeVault {
+                decimals: 6,
+                name: "Vault Token".to_string(),
+                symbol: "VLT".to_string(),
+                uri: "https://example.com/vault.json".to_string(),
+            }
+            .data(),
+        };
+        let message = Message::new(&[init_ix], Some(&admin.pubkey()));
+        let failed = svm
+            .send_transaction(Transaction::new(
+                &[&admin, &mint2022],
+                message,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Init with a non-executable hook program should fail");
+        let code: u32 = crate::error::VaultError::HookProgramNotExecutable.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+
+        // the account at the hook program address is no longer executable
+        svm.set_account(
+            get_tf_hook_program_address(),
+            SolanaAccount {
+                lamports: LAMPORTS_PER_SOL,
+                data: vec![],
+                owner: SYSTEM_PROGRAM,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .expect("Failed to overwrite the hook program account");
+        let failed = svm
+            .send_transaction(build_deposit_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Deposit through a non-executable hook program should fail");
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 100);
+    }
+
+    #[test]
+    fn test_unstake_keeps_deposit_cap() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetDepositLimits {
+                max_total_deposits: u64::MAX,
+                max_per_user: 100,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set deposit limits txn");
+
+        // moving the shares to the wallet does not free up room under the cap
+        let transaction =
+            build_unstake_transaction(&admin, config, token_program, 100, svm.latest_blockhash());
+        svm.send_transaction(transaction)
+            .expect("Failed to send unstake txn");
+        let position = get_position(&svm, admin.pubkey(), config);
+        assert_eq!(position.shares, 0);
+        assert_eq!(position.deposited, 100);
+
+        let failed = svm
+            .send_transaction(build_deposit_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Depositing again after unstaking should hit the cap");
+        let code: u32 = crate::error::VaultError::UserDepositCapExceeded.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        // staking shares from the wallet counts towards the cap as well
+        let holder = Keypair::new();
+        svm.airdrop(&holder.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to holder.");
+        let transaction = build_share_transfer_transaction(
+            &admin,
+            holder.pubkey(),
+            config,
+            token_program,
+            100,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to transfer shares");
+        svm.expire_blockhash();
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetDepositLimits {
+                max_total_deposits: u64::MAX,
+                max_per_user: 99,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set deposit limits txn");
+        let transaction = build_stake_transaction(
+            &holder,
+            config,
+            vault,
+            token_program,
+            100,
+            svm.latest_blockhash(),
+        );
+        let failed = svm
+            .send_transaction(transaction)
+            .expect_err("Staking above the cap should fail");
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        let transaction = build_stake_transaction(
+            &holder,
+            config,
+            vault,
+            token_program,
+            99,
+            svm.latest_blockhash(),
+        );
+        let tx = svm
+            .send_transaction(transaction)
+            .expect("Failed to send stake txn");
+        assert_eq!(get_position(&svm, holder.pubkey(), config).deposited, 99);
+
+        // Log transaction details
+        msg!("\n\n Capped stake successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_close_position_with_pending_withdrawal() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+        let vault_whitelist = get_whitelist_address(mint2022.pubkey(), vault);
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetWithdrawCooldown {
+                withdraw_cooldown: 3_600,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set cooldown txn");
+
+        // every share sits in the request, but the position is still needed to claim it
+        svm.send_transaction(build_request_withdraw_transaction(
+            &admin,
+            config,
+            100,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send request withdraw txn");
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 0);
+
+        let failed = svm
+            .send_transaction(build_close_position_transaction(
+                &admin,
+                config,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Closing with a pending withdrawal should fail");
+        let code: u32 = crate::error::VaultError::WithdrawalPending.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 3_600;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        svm.send_transaction(build_claim_withdraw_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+            vault_whitelist,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send claim withdraw txn");
+
+        let tx = svm
+            .send_transaction(build_close_position_transaction(
+                &admin,
+                config,
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send close position txn");
+        assert!(svm
+            .get_account(&get_amount_address(admin.pubkey(), config))
+            .is_none());
+
+        // Log transaction details
+        msg!("\n\n Close after claim successful");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
+    }
+
+    #[test]
+    fn test_anyone_can_expire_a_lock() {
+        let TestEnv {
+            mut svm,
+            admin,
+            mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        } = setup();
+
+        setup_deposit(
+            &mut svm,
+            &admin,
+            &mint2022,
+            token_program,
+            config,
+            vault,
+            user_ata,
+        );
+        let reward_mint = setup_rewards(&mut svm, &admin, config, 10);
+        svm.send_transaction(build_admin_transaction(
+            &admin,
+            config,
+            crate::instruction::SetLockupTier {
+                index: 1,
+                duration: 100,
+                multiplier_bps: 20_000,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set lockup tier txn");
+
+        // a second, unboosted staker holding 50 shares
+        let holder = Keypair::new();
+        svm.airdrop(&holder.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to holder.");
+        let transaction =
+            build_unstake_transaction(&admin, config, token_program, 50, svm.latest_blockhash());
+        svm.send_transaction(transaction)
+            .expect("Failed to send unstake txn");
+        let transaction = build_share_transfer_transaction(
+            &admin,
+            holder.pubkey(),
+            config,
+            token_program,
+            50,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to transfer shares");
+        let transaction = build_stake_transaction(
+            &holder,
+            config,
+            vault,
+            token_program,
+            50,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send stake txn");
+
+        // 100 shares locked at 2x for 100 seconds
+        svm.send_transaction(build_deposit_transaction_with_data(
             &admin,
             &mint2022,
             token_program,
             config,
             vault,
-            recent_blockhash,
+            user_ata,
+            crate::instruction::DepositLocked {
+                amount: 50,
+                tier: 1,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send locked deposit txn");
+
+        let failed = svm
+            .send_transaction(build_expire_lock_transaction(
+                &holder,
+                admin.pubkey(),
+                config,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Expiring a running lock should fail");
+        let code: u32 = crate::error::VaultError::PositionLocked.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
         );
 
-        let tx = svm.send_transaction(transaction).unwrap();
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 100;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+
+        // the other staker drops the boost while the owner stays idle
+        let tx = svm
+            .send_transaction(build_expire_lock_transaction(
+                &holder,
+                admin.pubkey(),
+                config,
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send expire lock txn");
+        let position = get_position(&svm, admin.pubkey(), config);
+        assert_eq!(position.multiplier_bps, crate::BPS_DENOMINATOR);
+        assert_eq!(position.stake, 100);
+        assert_eq!(get_config(&svm, config).total_stake, 150);
+
+        svm.expire_blockhash();
+        let failed = svm
+            .send_transaction(build_expire_lock_transaction(
+                &holder,
+                admin.pubkey(),
+                config,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Expiring the same lock twice should fail");
+        let code: u32 = crate::error::VaultError::NoLockToExpire.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+
+        // the holder's activity moves the accumulator on without boosting the expired lock
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 50;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+        let transaction = build_claim_rewards_transaction(
+            &holder,
+            config,
+            reward_mint,
+            anchor_spl::token::ID,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send claim rewards txn");
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 50;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+        let transaction = build_claim_rewards_transaction(
+            &admin,
+            config,
+            reward_mint,
+            anchor_spl::token::ID,
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(transaction)
+            .expect("Failed to send claim rewards txn");
+
+        // 200/250 of the first 100 seconds, then 100/150 of the next 100
+        assert_eq!(reward_balance(&svm, admin.pubkey(), reward_mint), 1_466);
 
         // Log transaction details
-        msg!("\n\nInit transaction sucessfull");
+        msg!("\n\n Expire lock successful");
         msg!("CUs Consumed: {}", tx.compute_units_consumed);
         msg!("Tx Signature: {}", tx.signature);
     }
 
     #[test]
-    fn test_mint() {
+    fn test_deposit_locked_keeps_tier_pairs() {
         let TestEnv {
             mut svm,
             admin,
@@ -353,45 +5174,78 @@ mod tests {
             user_ata,
         } = setup();
 
-        let recent_blockhash = svm.latest_blockhash();
-        let transaction1 = build_init_transaction(
+        setup_deposit(
+            &mut svm,
             &admin,
             &mint2022,
             token_program,
             config,
             vault,
-            recent_blockhash,
+            user_ata,
         );
-        let _tx1 = svm.send_transaction(transaction1).unwrap();
-
-        let amount = 1_000_000;
+        // a long, low tier and a short, high one
+        for (index, duration, multiplier_bps) in [(0, 1_000, 12_000), (1, 100, 20_000)] {
+            svm.send_transaction(build_admin_transaction(
+                &admin,
+                config,
+                crate::instruction::SetLockupTier {
+                    index,
+                    duration,
+                    multiplier_bps,
+                }
+                .data(),
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send set lockup tier txn");
+        }
 
-        let transaction2 = build_mint_transaction(
+        let now = svm.get_sysvar::<Clock>().unix_timestamp;
+        svm.send_transaction(build_deposit_transaction_with_data(
             &admin,
             &mint2022,
             token_program,
+            config,
+            vault,
             user_ata,
-            amount,
-            recent_blockhash,
-        );
-        let tx2 = svm
-            .send_transaction(transaction2)
-            .expect("Failed to send mint token transaction!");
+            crate::instruction::DepositLocked {
+                amount: 50,
+                tier: 0,
+            }
+            .data(),
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send locked deposit txn");
 
-        // Log transaction details
-        msg!("\n\n Mint transaction sucessfull");
-        msg!("CUs Consumed: {}", tx2.compute_units_consumed);
-        msg!("Tx Signature: {}", tx2.signature);
+        // the short tier's multiplier is not applied to the long lock
+        let tx = svm
+            .send_transaction(build_deposit_transaction_with_data(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                crate::instruction::DepositLocked {
+                    amount: 50,
+                    tier: 1,
+                }
+                .data(),
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send locked deposit txn");
+        let position = get_position(&svm, admin.pubkey(), config);
+        assert_eq!(position.locked_until, now + 1_000);
+        assert_eq!(position.multiplier_bps, 12_000);
+        assert_eq!(position.stake, 240);
 
-        let token_account = svm.get_account(&user_ata).unwrap();
-        let token_state = StateWithExtensions::<Account>::unpack(&token_account.data)
-            .expect("Failed to deserialize token account data");
-        msg!("token state: {:?}", token_state.base);
-        assert_eq!(token_state.base.amount, amount);
+        // Log transaction details
+        msg!("\n\n Locked deposit kept the longer tier");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+        msg!("Tx Signature: {}", tx.signature);
     }
 
     #[test]
-    fn test_initialize_transfer_hook() {
+    fn test_withdraw_needs_shares_to_burn() {
         let TestEnv {
             mut svm,
             admin,
@@ -399,90 +5253,107 @@ mod tests {
             token_program,
             config,
             vault,
-            user_ata: _,
+            user_ata,
         } = setup();
-        let recent_blockhash = svm.latest_blockhash();
-        let transaction1 = build_init_transaction(
+
+        setup_deposit(
+            &mut svm,
             &admin,
             &mint2022,
             token_program,
             config,
             vault,
-            recent_blockhash,
+            user_ata,
         );
-        let _tx1 = svm
-            .send_transaction(transaction1)
-            .expect("Failed to send init vault tx");
-
-        let transaction2 = build_init_tf_transaction(&admin, &mint2022, recent_blockhash);
-
-        let tx2 = svm
-            .send_transaction(transaction2)
-            .expect("Failed to send init tf hoook tx");
-
-        // Log transaction details
-        msg!("\n\n Init tf hook transaction sucessfull");
-        msg!("CUs Consumed: {}", tx2.compute_units_consumed);
-        msg!("Tx Signature: {}", tx2.signature);
-    }
-
-    #[test]
-    fn test_add_to_whitelist() {
-        let TestEnv {
-            mut svm,
-            admin,
-            mint2022: _,
-            token_program: _,
-            config: _,
-            vault,
-            user_ata: _,
-        } = setup();
-        let recent_blockhash = svm.latest_blockhash();
-        let transaction =
-            build_whitelist_transaction(&admin, vault, "add_to_whitelist", recent_blockhash);
-        let tx = svm
-            .send_transaction(transaction)
-            .expect("Failed to send init tf hoook tx");
+        let vault_whitelist = get_whitelist_address(mint2022.pubkey(), vault);
 
-        // Log transaction details
-        msg!("\n\n Add to whitelist transaction sucessfull");
-        msg!("CUs Consumed: {}", tx.compute_units_consumed);
-        msg!("Tx Signature: {}", tx.signature);
-    }
+        // a zero amount would burn no shares
+        let mut accounts =
+            get_withdraw_accounts(&admin, &mint2022, token_program, config, vault, user_ata)
+                .to_account_metas(None);
+        accounts.extend(get_hook_account_metas(
+            mint2022.pubkey(),
+            vault_whitelist,
+            get_whitelist_address(mint2022.pubkey(), user_ata),
+        ));
+        let withdraw_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::Withdraw { amount: 0 }.data(),
+        };
+        let message = Message::new(&[withdraw_ix], Some(&admin.pubkey()));
+        let failed = svm
+            .send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect_err("Withdrawing nothing should fail");
+        let code: u32 = crate::error::VaultError::ZeroShares.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
 
-    #[test]
-    fn test_remove_from_whitelist() {
-        let TestEnv {
-            mut svm,
-            admin,
-            mint2022: _,
-            token_program: _,
-            config: _,
+        // redeem every share but keep the position around
+        svm.send_transaction(build_withdraw_transaction(
+            &admin,
+            &mint2022,
+            token_program,
+            config,
             vault,
-            user_ata: _,
-        } = setup();
-        let recent_blockhash = svm.latest_blockhash();
-
-        let transaction1 =
-            build_whitelist_transaction(&admin, vault, "add_to_whitelist", recent_blockhash);
-        let _tx1 = svm
-            .send_transaction(transaction1)
-            .expect("Failed to send init tf hoook tx");
+            user_ata,
+            vault_whitelist,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send withdraw txn");
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 0);
 
-        let transaction =
-            build_whitelist_transaction(&admin, vault, "remove_from_whitelist", recent_blockhash);
-        let tx = svm
-            .send_transaction(transaction)
-            .expect("Failed to send remove from whitelist tx");
+        // tokens sent to a vault without shares are not claimable through the position
+        let mut donate_ix = spl_token_2022::instruction::transfer_checked(
+            &token_program,
+            &user_ata,
+            &mint2022.pubkey(),
+            &vault,
+            &admin.pubkey(),
+            &[],
+            100,
+            6,
+        )
+        .unwrap();
+        donate_ix.accounts.extend(get_hook_account_metas(
+            mint2022.pubkey(),
+            get_whitelist_address(mint2022.pubkey(), user_ata),
+            vault_whitelist,
+        ));
+        let message = Message::new(&[donate_ix], Some(&admin.pubkey()));
+        svm.send_transaction(Transaction::new(&[&admin], message, svm.latest_blockhash()))
+            .expect("Failed to donate to the vault");
+        svm.expire_blockhash();
 
-        // Log transaction details
-        msg!("\n\n Remove from whitelist transaction successful");
-        msg!("CUs Consumed: {}", tx.compute_units_consumed);
-        msg!("Tx Signature: {}", tx.signature);
+        let failed = svm
+            .send_transaction(build_withdraw_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                vault_whitelist,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Withdrawing without any shares outstanding should fail");
+        let code: u32 = crate::error::VaultError::AmountExceededUrDeposit.into();
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+        let vault_account = svm.get_account(&vault).unwrap();
+        let vault_state = StateWithExtensions::<Account>::unpack(&vault_account.data)
+            .expect("Failed to deserialize vault data");
+        assert_eq!(vault_state.base.amount, 100);
     }
 
     #[test]
-    fn test_deposit() {
+    fn test_pause_deposit_and_withdraw() {
         let TestEnv {
             mut svm,
             admin,
@@ -493,69 +5364,109 @@ mod tests {
             user_ata,
         } = setup();
 
-        let recent_blockhash = svm.latest_blockhash();
-        let transaction1 = build_init_transaction(
+        setup_deposit(
+            &mut svm,
             &admin,
             &mint2022,
             token_program,
             config,
             vault,
-            recent_blockhash,
+            user_ata,
         );
-        let _tx1 = svm
-            .send_transaction(transaction1)
-            .expect("Failed to send vault init tx");
-
-        let transaction2 = build_init_tf_transaction(&admin, &mint2022, recent_blockhash);
-        let _tx2 = svm
-            .send_transaction(transaction2)
-            .expect("Failed to send init tf hoook tx");
+        let vault_whitelist = get_whitelist_address(mint2022.pubkey(), vault);
+        let code: u32 = crate::error::VaultError::Paused.into();
 
-        let amount = 1_000_000;
-        let transaction3 = build_mint_transaction(
+        svm.send_transaction(build_set_pause_transaction(
             &admin,
-            &mint2022,
-            token_program,
-            user_ata,
-            amount,
-            recent_blockhash,
+            config,
+            crate::PAUSE_DEPOSIT,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set pause txn");
+        let failed = svm
+            .send_transaction(build_deposit_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Depositing while paused should fail");
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
         );
-        let _tx3 = svm
-            .send_transaction(transaction3)
-            .expect("Failed to send mint txn");
-
-        let token_account = svm.get_account(&user_ata).unwrap();
-        let token_state = StateWithExtensions::<Account>::unpack(&token_account.data)
-            .expect("Failed to deserialize token account data");
-        msg!("token state: {:?}", token_state.base);
 
-        let transaction4 =
-            build_whitelist_transaction(&admin, user_ata, "add_to_whitelist", recent_blockhash);
-        let _tx4 = svm
-            .send_transaction(transaction4)
-            .expect("Failed to send whitelist txn");
-
-        let transaction = build_deposit_transaction(
+        // pausing withdrawals alone lets deposits through again
+        svm.send_transaction(build_set_pause_transaction(
+            &admin,
+            config,
+            crate::PAUSE_WITHDRAW,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send set pause txn");
+        let failed = svm
+            .send_transaction(build_withdraw_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                vault_whitelist,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Withdrawing while paused should fail");
+        assert!(
+            format!("{:?}", failed.err).contains(&format!("Custom({})", code)),
+            "unexpected error: {:?}",
+            failed.err
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(build_deposit_transaction(
             &admin,
             &mint2022,
             token_program,
             config,
             vault,
             user_ata,
-            recent_blockhash,
-        );
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send Deposit txn");
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 200);
+
+        svm.send_transaction(build_set_pause_transaction(
+            &admin,
+            config,
+            0,
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send unpause txn");
         let tx = svm
-            .send_transaction(transaction)
-            .expect("Failed to send Deposit txn");
+            .send_transaction(build_withdraw_transaction(
+                &admin,
+                &mint2022,
+                token_program,
+                config,
+                vault,
+                user_ata,
+                vault_whitelist,
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send withdraw txn after unpausing");
+        assert_eq!(get_position(&svm, admin.pubkey(), config).shares, 100);
 
         // Log transaction details
-        msg!("\n\n Desposit transaction successful");
+        msg!("\n\n Deposit and withdraw pause successful");
         msg!("CUs Consumed: {}", tx.compute_units_consumed);
         msg!("Tx Signature: {}", tx.signature);
     }
 
     #[test]
-    fn test_withdraw() {
+    fn test_hook_state_is_per_mint() {
         let TestEnv {
             mut svm,
             admin,
@@ -566,47 +5477,67 @@ mod tests {
             user_ata,
         } = setup();
 
-        let recent_blockhash = svm.latest_blockhash();
-        let transfer_hook_program = get_tf_hook_program_address();
-        let vault_whitelist =
-            Pubkey::find_program_address(&[b"whitelist", vault.as_ref()], &transfer_hook_program).0;
-        let transaction1 = build_init_transaction(
-            &admin,
-            &mint2022,
-            token_program,
-            config,
-            vault,
-            recent_blockhash,
+        // a second mint served by the same hook program
+        let other_mint = Keypair::new();
+        let other_config =
+            Pubkey::find_program_address(&[b"config", other_mint.pubkey().as_ref()], &PROGRAM_ID).0;
+        let other_vault = associated_token::get_associated_token_address_with_program_id(
+            &other_config,
+            &other_mint.pubkey(),
+            &token_program,
+        );
+        let other_user_ata = associated_token::get_associated_token_address_with_program_id(
+            &admin.pubkey(),
+            &other_mint.pubkey(),
+            &token_program,
         );
-        let _tx1 = svm
-            .send_transaction(transaction1)
-            .expect("Failed to send vault init tx");
 
-        let transaction2 = build_init_tf_transaction(&admin, &mint2022, recent_blockhash);
-        let _tx2 = svm
-            .send_transaction(transaction2)
-            .expect("Failed to send init tf hoook tx");
+        // both mints key their entries by owner, so the same admin key would match
+        let recent_blockhash = svm.latest_blockhash();
+        for (mint, config, vault, user_ata) in [
+            (&mint2022, config, vault, user_ata),
+            (&other_mint, other_config, other_vault, other_user_ata),
+        ] {
+            svm.send_transaction(build_init_transaction(
+                &admin,
+                mint,
+                token_program,
+                config,
+                vault,
+                recent_blockhash,
+            ))
+            .expect("Failed to send vault init tx");
+            svm.send_transaction(build_init_tf_transaction(&admin, mint, recent_blockhash))
+                .expect("Failed to send init tf hoook tx");
+            svm.send_transaction(build_mint_transaction(
+                &admin,
+                mint,
+                token_program,
+                config,
+                user_ata,
+                1_000_000,
+                recent_blockhash,
+            ))
+            .expect("Failed to send mint txn");
+            svm.send_transaction(build_hook_config_transaction(
+                &admin,
+                mint.pubkey(),
+                "set_hook_mode",
+                1,
+                recent_blockhash,
+            ))
+            .expect("Failed to switch to owner mode");
+        }
 
-        let amount = 1_000_000;
-        let transaction3 = build_mint_transaction(
+        svm.send_transaction(build_whitelist_transaction(
             &admin,
-            &mint2022,
-            token_program,
-            user_ata,
-            amount,
+            mint2022.pubkey(),
+            admin.pubkey(),
+            "add_to_whitelist",
             recent_blockhash,
-        );
-        let _tx3 = svm
-            .send_transaction(transaction3)
-            .expect("Failed to send mint txn");
-
-        let transaction4 =
-            build_whitelist_transaction(&admin, user_ata, "add_to_whitelist", recent_blockhash);
-        let _tx4 = svm
-            .send_transaction(transaction4)
-            .expect("Failed to send whitelist it txn");
-
-        let transaction5 = build_deposit_transaction(
+        ))
+        .expect("Failed to send whitelist txn");
+        svm.send_transaction(build_owner_mode_deposit_transaction(
             &admin,
             &mint2022,
             token_program,
@@ -614,33 +5545,71 @@ mod tests {
             vault,
             user_ata,
             recent_blockhash,
-        );
-        let _tx5 = svm
-            .send_transaction(transaction5)
-            .expect("Failed to send Deposit txn");
+        ))
+        .expect("Failed to send Deposit txn");
 
-        let transaction6 =
-            build_whitelist_transaction(&admin, vault, "add_to_whitelist", recent_blockhash);
-        let _tx6 = svm
-            .send_transaction(transaction6)
-            .expect("Failed to send whitelist it txn");
+        // the owner's entry for the first mint does not cover the second
+        let failed = svm
+            .send_transaction(build_owner_mode_deposit_transaction(
+                &admin,
+                &other_mint,
+                token_program,
+                other_config,
+                other_vault,
+                other_user_ata,
+                recent_blockhash,
+            ))
+            .expect_err("An entry for another mint should not authorize the transfer");
+        // WhitelistError::NotWhitelisted
+        assert!(format!("{:?}", failed.err).contains("Custom(6000)"));
 
-        let transaction = build_withdraw_transaction(
+        // nor does switching the first mint's hook config to a deny-list
+        svm.send_transaction(build_hook_config_transaction(
             &admin,
-            &mint2022,
-            token_program,
-            config,
-            vault,
-            user_ata,
-            vault_whitelist,
+            mint2022.pubkey(),
+            "set_deny_list",
+            1,
             recent_blockhash,
-        );
+        ))
+        .expect("Failed to enable the deny-list");
+        svm.expire_blockhash();
+        let failed = svm
+            .send_transaction(build_owner_mode_deposit_transaction(
+                &admin,
+                &other_mint,
+                token_program,
+                other_config,
+                other_vault,
+                other_user_ata,
+                svm.latest_blockhash(),
+            ))
+            .expect_err("Another mint's hook config should not authorize the transfer");
+        assert!(format!("{:?}", failed.err).contains("Custom(6000)"));
 
+        svm.send_transaction(build_whitelist_transaction(
+            &admin,
+            other_mint.pubkey(),
+            admin.pubkey(),
+            "add_to_whitelist",
+            svm.latest_blockhash(),
+        ))
+        .expect("Failed to send whitelist txn");
+        svm.expire_blockhash();
         let tx = svm
-            .send_transaction(transaction)
-            .expect("Failed to send withdraw txn");
+            .send_transaction(build_owner_mode_deposit_transaction(
+                &admin,
+                &other_mint,
+                token_program,
+                other_config,
+                other_vault,
+                other_user_ata,
+                svm.latest_blockhash(),
+            ))
+            .expect("Failed to send Deposit txn");
+        assert_eq!(get_position(&svm, admin.pubkey(), other_config).shares, 100);
+
         // Log transaction details
-        msg!("\n\n Withdraw transaction successful");
+        msg!("\n\n Per-mint hook state deposit successful");
         msg!("CUs Consumed: {}", tx.compute_units_consumed);
         msg!("Tx Signature: {}", tx.signature);
     }
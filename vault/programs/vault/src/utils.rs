@@ -0,0 +1,73 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use spl_token_2022::{
+    extension::{transfer_hook, StateWithExtensions},
+    state::Mint,
+};
+
+use crate::error::VaultError;
+
+/// The program set in the mint's `TransferHook` extension, if it has one.
+/// Classic SPL Token mints never do.
+pub fn transfer_hook_program_id(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<Mint>::unpack(&data)?;
+    Ok(transfer_hook::get_program_id(&state))
+}
+
+/// Checks the transfer-hook accounts for `mint` in `remaining_accounts`, laid
+/// out as the hook program, its `ExtraAccountMetaList`, then the extra metas.
+/// Mints without a hook need none. `invoke_transfer_checked` resolves every
+/// extra meta from the list and fails if any resolved account is missing.
+pub fn hook_accounts<'a, 'info>(
+    mint: &AccountInfo<'info>,
+    expected_program: Pubkey,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<&'a [AccountInfo<'info>]> {
+    let program_id = transfer_hook_program_id(mint)?.unwrap_or_default();
+    require_keys_eq!(
+        program_id,
+        expected_program,
+        VaultError::HookProgramMismatch
+    );
+    if program_id == Pubkey::default() {
+        return Ok(&[]);
+    }
+
+    let [program, extra_account_meta_list, ..] = remaining_accounts else {
+        return err!(VaultError::MissingHookAccounts);
+    };
+    require_keys_eq!(program.key(), program_id, VaultError::HookProgramMismatch);
+    require!(program.executable, VaultError::HookProgramNotExecutable);
+
+    let expected_meta_list =
+        Pubkey::find_program_address(&[b"extra-account-metas", mint.key.as_ref()], &program_id).0;
+    require_keys_eq!(
+        extra_account_meta_list.key(),
+        expected_meta_list,
+        VaultError::InvalidExtraAccountMetaList
+    );
+    Ok(remaining_accounts)
+}
+
+/// Tops `account` up to the rent-exempt minimum for its current size. Token-2022
+/// grows the mint for variable-length extensions but leaves funding to the caller.
+pub fn top_up_rent<'info>(
+    account: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let minimum = Rent::get()?.minimum_balance(account.data_len());
+    let shortfall = minimum.saturating_sub(account.lamports());
+    if shortfall == 0 {
+        return Ok(());
+    }
+
+    let cpi_accounts = Transfer {
+        from: payer,
+        to: account,
+    };
+    transfer(CpiContext::new(system_program, cpi_accounts), shortfall)
+}
@@ -2,3 +2,26 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+/// Whitelist entries are keyed by the source token account.
+#[constant]
+pub const MODE_TOKEN_ACCOUNT: u8 = 0;
+
+/// Whitelist entries are keyed by the source token account's owner.
+#[constant]
+pub const MODE_OWNER: u8 = 1;
+
+/// The sender must be whitelisted.
+#[constant]
+pub const FLAG_CHECK_SOURCE: u8 = 1 << 0;
+
+/// The receiver must be whitelisted.
+#[constant]
+pub const FLAG_CHECK_DESTINATION: u8 = 1 << 1;
+
+/// Checked accounts are rejected only if they have a `Blocked` entry.
+#[constant]
+pub const FLAG_DENY_LIST: u8 = 1 << 2;
+
+/// The flags `set_check_policy` may set.
+pub const CHECK_POLICY_MASK: u8 = FLAG_CHECK_SOURCE | FLAG_CHECK_DESTINATION;
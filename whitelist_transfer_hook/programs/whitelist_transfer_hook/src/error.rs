@@ -10,4 +10,16 @@ pub enum WhitelistError {
     NotTransferring,
     #[msg("This account is not the admin")]
     NotAdmin,
+    #[msg("This account is not the hook config authority")]
+    NotAuthority,
+    #[msg("Unknown hook mode")]
+    InvalidMode,
+    #[msg("Check policy must cover the source, the destination or both")]
+    InvalidCheckPolicy,
+    #[msg("This account is blocked")]
+    Blocked,
+    #[msg("This account is already blocked")]
+    AlreadyBlocked,
+    #[msg("This account is not blocked")]
+    NotBlocked,
 }
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct WhitelistAdded {
+    pub mint: Pubkey,
+    pub address: Pubkey,
+    /// Hook config mode at the time, i.e. whether `address` is a token account or an owner.
+    pub mode: u8,
+}
+
+#[event]
+pub struct WhitelistRemoved {
+    pub mint: Pubkey,
+    pub address: Pubkey,
+    /// Hook config mode at the time, i.e. whether `address` is a token account or an owner.
+    pub mode: u8,
+}
+
+#[event]
+pub struct BlocklistAdded {
+    pub mint: Pubkey,
+    pub address: Pubkey,
+    /// Hook config mode at the time, i.e. whether `address` is a token account or an owner.
+    pub mode: u8,
+}
+
+#[event]
+pub struct BlocklistRemoved {
+    pub mint: Pubkey,
+    pub address: Pubkey,
+    /// Hook config mode at the time, i.e. whether `address` is a token account or an owner.
+    pub mode: u8,
+}
+
+#[event]
+pub struct WhitelistAdminChanged {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct HookAuthorityChanged {
+    pub mint: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct TransferApproved {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
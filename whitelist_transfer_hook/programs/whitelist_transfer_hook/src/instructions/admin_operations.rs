@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::error::WhitelistError;
+use crate::program::WhitelistTransferHook;
+use crate::{HookAuthorityChanged, HookConfig, WhitelistAdmin, WhitelistAdminChanged};
+
+#[derive(Accounts)]
+pub struct InitializeWhitelistAdmin<'info> {
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = 8 + WhitelistAdmin::INIT_SPACE,
+        seeds = [b"whitelist-admin"],
+        bump
+    )]
+    pub whitelist_admin: Account<'info, WhitelistAdmin>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ WhitelistError::NotAdmin)]
+    pub program: Program<'info, WhitelistTransferHook>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ WhitelistError::NotAdmin)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl InitializeWhitelistAdmin<'_> {
+    pub fn init_whitelist_admin(
+        &mut self,
+        admin: Pubkey,
+        bumps: &InitializeWhitelistAdminBumps,
+    ) -> Result<()> {
+        self.whitelist_admin.set_inner(WhitelistAdmin {
+            admin,
+            bump: bumps.whitelist_admin,
+        });
+
+        emit!(WhitelistAdminChanged {
+            old_admin: Pubkey::default(),
+            new_admin: admin,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetWhitelistAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"whitelist-admin"],
+        bump = whitelist_admin.bump,
+        has_one = admin @ WhitelistError::NotAdmin
+    )]
+    pub whitelist_admin: Account<'info, WhitelistAdmin>,
+}
+
+impl SetWhitelistAdmin<'_> {
+    pub fn set_whitelist_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        self.whitelist_admin.admin = new_admin;
+
+        emit!(WhitelistAdminChanged {
+            old_admin: self.admin.key(),
+            new_admin,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetHookAuthority<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"whitelist-admin"],
+        bump = whitelist_admin.bump,
+        has_one = admin @ WhitelistError::NotAdmin
+    )]
+    pub whitelist_admin: Account<'info, WhitelistAdmin>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"hook-config", mint.key().as_ref()],
+        bump = hook_config.bump,
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+}
+
+impl SetHookAuthority<'_> {
+    /// Hands a mint's hook config, and with it its lists, to a new authority.
+    pub fn set_hook_authority(&mut self, new_authority: Pubkey) -> Result<()> {
+        let old_authority = self.hook_config.authority;
+        self.hook_config.authority = new_authority;
+
+        emit!(HookAuthorityChanged {
+            mint: self.mint.key(),
+            old_authority,
+            new_authority,
+        });
+        Ok(())
+    }
+}
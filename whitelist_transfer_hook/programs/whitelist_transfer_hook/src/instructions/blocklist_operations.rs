@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::error::WhitelistError;
+use crate::{Blocked, BlocklistAdded, BlocklistRemoved, HookConfig};
+
+#[derive(Accounts)]
+#[instruction(account: Pubkey)]
+pub struct BlocklistOperations<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // only this mint's hook config authority manages its lists
+    #[account(
+        seeds = [b"hook-config", mint.key().as_ref()],
+        bump = hook_config.bump,
+        has_one = authority @ WhitelistError::NotAuthority,
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + Blocked::INIT_SPACE,
+        seeds = [b"blocked", mint.key().as_ref(), account.as_ref()],
+        bump
+    )]
+    pub blocked: Account<'info, Blocked>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl BlocklistOperations<'_> {
+    pub fn add_to_blocklist(
+        &mut self,
+        account: Pubkey,
+        bumps: &BlocklistOperationsBumps,
+    ) -> Result<()> {
+        if self.blocked.address == account {
+            return err!(WhitelistError::AlreadyBlocked);
+        }
+
+        self.blocked.set_inner(Blocked {
+            address: account,
+            bump: bumps.blocked,
+        });
+
+        emit!(BlocklistAdded {
+            mint: self.mint.key(),
+            address: account,
+            mode: self.hook_config.mode,
+        });
+        Ok(())
+    }
+
+    /// Closes the entry, so the hook sees the account as uninitialized again.
+    pub fn remove_from_blocklist(&mut self, account: Pubkey) -> Result<()> {
+        if self.blocked.address != account {
+            return err!(WhitelistError::NotBlocked);
+        }
+
+        self.blocked.close(self.authority.to_account_info())?;
+
+        emit!(BlocklistRemoved {
+            mint: self.mint.key(),
+            address: account,
+            mode: self.hook_config.mode,
+        });
+        Ok(())
+    }
+}
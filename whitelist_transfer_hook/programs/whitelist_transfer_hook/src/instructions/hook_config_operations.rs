@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+use crate::error::WhitelistError;
+use crate::{
+    HookConfig, InitializeExtraAccountMetaList, CHECK_POLICY_MASK, FLAG_DENY_LIST, MODE_OWNER,
+    MODE_TOKEN_ACCOUNT,
+};
+
+#[derive(Accounts)]
+pub struct HookConfigOperations<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"hook-config", mint.key().as_ref()],
+        bump = hook_config.bump,
+        has_one = authority @ WhitelistError::NotAuthority,
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    /// CHECK: ExtraAccountMetaList Account, must use these seeds
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+impl HookConfigOperations<'_> {
+    /// Switches what whitelist entries are keyed by and rewrites the extra
+    /// account metas to derive them the same way. Existing entries are kept.
+    pub fn set_hook_mode(&mut self, mode: u8) -> Result<()> {
+        require!(
+            mode == MODE_TOKEN_ACCOUNT || mode == MODE_OWNER,
+            WhitelistError::InvalidMode
+        );
+        self.hook_config.mode = mode;
+        self.update_extra_account_metas()
+    }
+
+    /// Chooses which side of a transfer must be whitelisted: the source, the
+    /// destination, or both.
+    pub fn set_check_policy(&mut self, policy: u8) -> Result<()> {
+        require!(
+            policy != 0 && policy & !CHECK_POLICY_MASK == 0,
+            WhitelistError::InvalidCheckPolicy
+        );
+        self.hook_config.flags = (self.hook_config.flags & !CHECK_POLICY_MASK) | policy;
+        Ok(())
+    }
+
+    /// Switches between checking `Whitelist` entries and `Blocked` entries,
+    /// rewriting the extra account metas to match.
+    pub fn set_deny_list(&mut self, enabled: bool) -> Result<()> {
+        if enabled {
+            self.hook_config.flags |= FLAG_DENY_LIST;
+        } else {
+            self.hook_config.flags &= !FLAG_DENY_LIST;
+        }
+        self.update_extra_account_metas()
+    }
+
+    fn update_extra_account_metas(&mut self) -> Result<()> {
+        ExtraAccountMetaList::update::<ExecuteInstruction>(
+            &mut self.extra_account_meta_list.try_borrow_mut_data()?,
+            &InitializeExtraAccountMetaList::extra_account_metas(
+                self.hook_config.mode,
+                self.hook_config.flags,
+            )?,
+        )?;
+        Ok(())
+    }
+}
@@ -4,36 +4,116 @@ use spl_tlv_account_resolution::{
     account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
 };
 
+use crate::error::WhitelistError;
+use crate::{
+    HookConfig, WhitelistAdmin, FLAG_CHECK_SOURCE, FLAG_DENY_LIST, MODE_OWNER, MODE_TOKEN_ACCOUNT,
+};
+
 #[derive(Accounts)]
 pub struct InitializeExtraAccountMetaList<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    // the program-wide admin creates every hook config and its authority
+    #[account(
+        seeds = [b"whitelist-admin"],
+        bump = whitelist_admin.bump,
+        constraint = whitelist_admin.admin == payer.key() @ WhitelistError::NotAdmin,
+    )]
+    pub whitelist_admin: Account<'info, WhitelistAdmin>,
+
     /// CHECK: ExtraAccountMetaList Account, must use these seeds
     #[account(
         init,
         payer=payer,
         seeds=[b"extra-account-metas", mint.key().as_ref()],
         bump,
-        space =  ExtraAccountMetaList::size_of(InitializeExtraAccountMetaList::extra_account_metas()?.len())?,
+        space =  ExtraAccountMetaList::size_of(InitializeExtraAccountMetaList::extra_account_metas(MODE_TOKEN_ACCOUNT, FLAG_CHECK_SOURCE)?.len())?,
     )]
     pub extra_account_meta_list: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + HookConfig::INIT_SPACE,
+        seeds = [b"hook-config", mint.key().as_ref()],
+        bump
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    // only the mint's transfer hook authority may claim its config
+    #[account(extensions::transfer_hook::authority = payer)]
     pub mint: InterfaceAccount<'info, Mint>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> InitializeExtraAccountMetaList<'info> {
-    pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    pub fn init_hook_config(&mut self, bumps: &InitializeExtraAccountMetaListBumps) -> Result<()> {
+        self.hook_config.set_inner(HookConfig {
+            mint: self.mint.key(),
+            authority: self.payer.key(),
+            mode: MODE_TOKEN_ACCOUNT,
+            flags: FLAG_CHECK_SOURCE,
+            bump: bumps.hook_config,
+        });
+        Ok(())
+    }
+
+    /// Resolved after the five Execute accounts: the mint's hook config at
+    /// index 5, then the whitelist entries of the source at index 6 and the
+    /// destination at index 7. In owner mode the entries are derived from the
+    /// owner field of each token account, and in deny-list mode they are the
+    /// `Blocked` entries instead.
+    pub fn extra_account_metas(mode: u8, flags: u8) -> Result<Vec<ExtraAccountMeta>> {
         msg!("InitializeExtraAccountMetaList::extra_account_metas: constructing meta list");
-        Ok(vec![ExtraAccountMeta::new_with_seeds(
-            &[
-                Seed::Literal {
-                    bytes: b"whitelist".to_vec(),
-                },
-                Seed::AccountKey { index: 0 },
-            ],
-            false,
-            false,
-        )?])
+        let prefix: &[u8] = if flags & FLAG_DENY_LIST != 0 {
+            b"blocked"
+        } else {
+            b"whitelist"
+        };
+        let entry_seed = |account_index: u8| match mode {
+            MODE_OWNER => Seed::AccountData {
+                account_index,
+                data_index: 32,
+                length: 32,
+            },
+            _ => Seed::AccountKey {
+                index: account_index,
+            },
+        };
+        Ok(vec![
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"hook-config".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                ],
+                false,
+                false,
+            )?,
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: prefix.to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    entry_seed(0),
+                ],
+                false,
+                false,
+            )?,
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: prefix.to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    entry_seed(2),
+                ],
+                false,
+                false,
+            )?,
+        ])
     }
 }
@@ -1,7 +1,13 @@
+pub mod admin_operations;
+pub mod blocklist_operations;
+pub mod hook_config_operations;
 pub mod init_extra_accounts_meta;
 pub mod transfer_hook;
 pub mod whitelist_operations;
 
+pub use admin_operations::*;
+pub use blocklist_operations::*;
+pub use hook_config_operations::*;
 pub use init_extra_accounts_meta::*;
 pub use transfer_hook::*;
 pub use whitelist_operations::*;
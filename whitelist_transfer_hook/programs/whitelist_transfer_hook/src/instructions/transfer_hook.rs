@@ -9,7 +9,7 @@ use anchor_spl::token_2022::spl_token_2022::pod::PodAccount;
 use anchor_spl::token_interface::{Mint, TokenAccount};
 
 use crate::error::WhitelistError;
-use crate::Whitelist;
+use crate::{HookConfig, TransferApproved, Whitelist};
 
 #[derive(Accounts)]
 pub struct TransferHook<'info> {
@@ -19,7 +19,6 @@ pub struct TransferHook<'info> {
     )]
     pub source_token: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mint::decimals = 6)]
     pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -38,15 +37,37 @@ pub struct TransferHook<'info> {
     pub extra_account_meta_list: UncheckedAccount<'info>,
 
     #[account(
-        seeds=[b"whitelist", source_token.key().as_ref()],
-        bump=whitelist.bump
+        seeds=[b"hook-config", mint.key().as_ref()],
+        bump=hook_config.bump
     )]
-    pub whitelist: Account<'info, Whitelist>,
+    pub hook_config: Account<'info, HookConfig>,
+
+    /// CHECK: source `Whitelist` or `Blocked` entry, may be uninitialized
+    #[account(
+        seeds=[
+            hook_config.entry_prefix(),
+            mint.key().as_ref(),
+            hook_config.entry_key(source_token.key(), owner.key()).as_ref()
+        ],
+        bump
+    )]
+    pub source_entry: UncheckedAccount<'info>,
+
+    /// CHECK: destination `Whitelist` or `Blocked` entry, may be uninitialized
+    #[account(
+        seeds=[
+            hook_config.entry_prefix(),
+            mint.key().as_ref(),
+            hook_config.entry_key(destination_token.key(), destination_token.owner).as_ref()
+        ],
+        bump
+    )]
+    pub destination_entry: UncheckedAccount<'info>,
 }
 
 impl TransferHook<'_> {
     /// This function is called when the transfer hook is executed.
-    pub fn transfer_hook(&mut self, _amount: u64) -> Result<()> {
+    pub fn transfer_hook(&mut self, amount: u64) -> Result<()> {
         // Entry log for debugging
         msg!(
             "transfer_hook: invoked. source={}, destination={}, owner={}, mint={}, amount={}",
@@ -54,24 +75,51 @@ impl TransferHook<'_> {
             self.destination_token.key(),
             self.owner.key(),
             self.mint.key(),
-            _amount
+            amount
         );
 
         // Fail this instruction if it is not called from within a transfer hook
         self.check_is_transferring()?;
         msg!("transfer_hook: passed check_is_transferring");
 
-        if self.whitelist.address != self.source_token.key() {
-            msg!(
-                "transfer_hook: owner {} is not whitelisted (whitelist.address={})",
-                self.source_token.key(),
-                self.whitelist.address
-            );
-            return err!(WhitelistError::NotWhitelisted);
+        if self.hook_config.checks_source() {
+            let entry_key = self
+                .hook_config
+                .entry_key(self.source_token.key(), self.owner.key());
+            self.check_entry(&self.source_entry, entry_key)?;
+        }
+
+        if self.hook_config.checks_destination() {
+            let entry_key = self
+                .hook_config
+                .entry_key(self.destination_token.key(), self.destination_token.owner);
+            self.check_entry(&self.destination_entry, entry_key)?;
         }
 
-        msg!("transfer_hook: whitelist check passed for owner {}", self.owner.key());
+        msg!("transfer_hook: list check passed for owner {}", self.owner.key());
 
+        emit!(TransferApproved {
+            mint: self.mint.key(),
+            source: self.source_token.key(),
+            destination: self.destination_token.key(),
+            owner: self.owner.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    /// In deny-list mode any `Blocked` entry rejects; an uninitialized one means
+    /// the account was never blocked. Otherwise a matching `Whitelist` entry is required.
+    fn check_entry(&self, entry: &AccountInfo, key: Pubkey) -> Result<()> {
+        if self.hook_config.is_deny_list() {
+            if entry.owner == &crate::ID && !entry.data_is_empty() {
+                msg!("transfer_hook: {} is blocked", key);
+                return err!(WhitelistError::Blocked);
+            }
+        } else if !is_whitelisted(entry, key)? {
+            msg!("transfer_hook: {} is not whitelisted", key);
+            return err!(WhitelistError::NotWhitelisted);
+        }
         Ok(())
     }
 
@@ -110,3 +158,12 @@ impl TransferHook<'_> {
         Ok(())
     }
 }
+
+/// An entry counts only if this program created it for `key`.
+fn is_whitelisted(entry: &AccountInfo, key: Pubkey) -> Result<bool> {
+    if entry.owner != &crate::ID || entry.data_is_empty() {
+        return Ok(false);
+    }
+    let whitelist = Whitelist::try_deserialize(&mut &entry.try_borrow_data()?[..])?;
+    Ok(whitelist.address == key)
+}
@@ -1,22 +1,30 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
 
 use crate::error::WhitelistError;
-use crate::Whitelist;
+use crate::{HookConfig, Whitelist, WhitelistAdded, WhitelistRemoved};
 
 #[derive(Accounts)]
 #[instruction(token_account: Pubkey)]
 pub struct WhitelistOperations<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // only this mint's hook config authority manages its lists
     #[account(
-        mut,
-        //address = <the admin address> (to be more strict)
+        seeds = [b"hook-config", mint.key().as_ref()],
+        bump = hook_config.bump,
+        has_one = authority @ WhitelistError::NotAuthority,
     )]
-    pub admin: Signer<'info>,
+    pub hook_config: Account<'info, HookConfig>,
 
     #[account(
         init_if_needed,
-        payer = admin,
+        payer = authority,
         space= 8 + Whitelist::INIT_SPACE,
-        seeds=[b"whitelist", token_account.key().as_ref()],
+        seeds=[b"whitelist", mint.key().as_ref(), token_account.key().as_ref()],
         bump
     )]
     pub whitelist: Account<'info, Whitelist>,
@@ -38,6 +46,12 @@ impl WhitelistOperations<'_> {
             address: token_account,
             bump: bumps.whitelist,
         });
+
+        emit!(WhitelistAdded {
+            mint: self.mint.key(),
+            address: token_account,
+            mode: self.hook_config.mode,
+        });
         Ok(())
     }
 
@@ -46,7 +60,13 @@ impl WhitelistOperations<'_> {
             return err!(WhitelistError::AlreadyWhitelisted);
         }
 
-        self.whitelist.close(self.admin.to_account_info())?;
+        self.whitelist.close(self.authority.to_account_info())?;
+
+        emit!(WhitelistRemoved {
+            mint: self.mint.key(),
+            address: token_account,
+            mode: self.hook_config.mode,
+        });
         Ok(())
     }
 }
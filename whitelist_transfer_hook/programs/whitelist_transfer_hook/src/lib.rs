@@ -1,11 +1,13 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -26,7 +28,7 @@ pub mod whitelist_transfer_hook {
     pub fn initialize_transfer_hook(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
         msg!("Initializing Transfer Hook...");
 
-        let extra_account_metas = InitializeExtraAccountMetaList::extra_account_metas()?;
+        let extra_account_metas = InitializeExtraAccountMetaList::extra_account_metas(MODE_TOKEN_ACCOUNT, FLAG_CHECK_SOURCE)?;
         msg!("Extra account Metas Length: {:?}", extra_account_metas);
         msg!(
             "Extra account Metas Length: {:?}",
@@ -38,7 +40,7 @@ pub mod whitelist_transfer_hook {
             &extra_account_metas,
         )?;
 
-        Ok(())
+        ctx.accounts.init_hook_config(&ctx.bumps)
     }
 
     #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
@@ -46,6 +48,30 @@ pub mod whitelist_transfer_hook {
         ctx.accounts.transfer_hook(amount)
     }
 
+    pub fn initialize_whitelist_admin(ctx: Context<InitializeWhitelistAdmin>, admin: Pubkey) -> Result<()> {
+        ctx.accounts.init_whitelist_admin(admin, &ctx.bumps)
+    }
+
+    pub fn set_whitelist_admin(ctx: Context<SetWhitelistAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.set_whitelist_admin(new_admin)
+    }
+
+    pub fn set_hook_authority(ctx: Context<SetHookAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.set_hook_authority(new_authority)
+    }
+
+    pub fn set_hook_mode(ctx: Context<HookConfigOperations>, mode: u8) -> Result<()> {
+        ctx.accounts.set_hook_mode(mode)
+    }
+
+    pub fn set_check_policy(ctx: Context<HookConfigOperations>, policy: u8) -> Result<()> {
+        ctx.accounts.set_check_policy(policy)
+    }
+
+    pub fn set_deny_list(ctx: Context<HookConfigOperations>, enabled: bool) -> Result<()> {
+        ctx.accounts.set_deny_list(enabled)
+    }
+
     pub fn add_to_whitelist(ctx: Context<WhitelistOperations>, token_account: Pubkey) -> Result<()> {
         ctx.accounts.add_to_whitelist(token_account, &ctx.bumps)
     }
@@ -53,4 +79,12 @@ pub mod whitelist_transfer_hook {
     pub fn remove_from_whitelist(ctx: Context<WhitelistOperations>, token_account: Pubkey) -> Result<()> {
         ctx.accounts.remove_from_whitelist(token_account)
     }
+
+    pub fn add_to_blocklist(ctx: Context<BlocklistOperations>, account: Pubkey) -> Result<()> {
+        ctx.accounts.add_to_blocklist(account, &ctx.bumps)
+    }
+
+    pub fn remove_from_blocklist(ctx: Context<BlocklistOperations>, account: Pubkey) -> Result<()> {
+        ctx.accounts.remove_from_blocklist(account)
+    }
 }
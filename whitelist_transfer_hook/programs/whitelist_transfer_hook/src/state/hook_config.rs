@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{FLAG_CHECK_DESTINATION, FLAG_CHECK_SOURCE, FLAG_DENY_LIST, MODE_OWNER};
+
+#[account]
+#[derive(InitSpace)]
+pub struct HookConfig {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub mode: u8,
+    pub flags: u8,
+    pub bump: u8,
+}
+
+impl HookConfig {
+    /// The address an entry is keyed by for a token account.
+    pub fn entry_key(&self, token_account: Pubkey, owner: Pubkey) -> Pubkey {
+        match self.mode {
+            MODE_OWNER => owner,
+            _ => token_account,
+        }
+    }
+
+    pub fn checks_source(&self) -> bool {
+        self.flags & FLAG_CHECK_SOURCE != 0
+    }
+
+    pub fn checks_destination(&self) -> bool {
+        self.flags & FLAG_CHECK_DESTINATION != 0
+    }
+
+    pub fn is_deny_list(&self) -> bool {
+        self.flags & FLAG_DENY_LIST != 0
+    }
+
+    /// Seed prefix of the entries checked on transfer.
+    pub fn entry_prefix(&self) -> &'static [u8] {
+        if self.is_deny_list() {
+            b"blocked"
+        } else {
+            b"whitelist"
+        }
+    }
+}
@@ -1,3 +1,9 @@
+pub mod blocked;
+pub mod hook_config;
 pub mod whitelist;
+pub mod whitelist_admin;
 
+pub use blocked::*;
+pub use hook_config::*;
 pub use whitelist::*;
+pub use whitelist_admin::*;
@@ -0,0 +1,8 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct WhitelistAdmin {
+    pub admin: Pubkey,
+    pub bump: u8,
+}